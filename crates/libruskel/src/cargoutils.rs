@@ -2,8 +2,12 @@ use std::fs;
 use std::io::Write;
 use std::path::{absolute, Path, PathBuf};
 
-use cargo::{core::Workspace, ops, util::context::GlobalContext};
-use semver::Version;
+use cargo::{
+    core::{dependency::DepKind, PackageId, Workspace},
+    ops,
+    util::context::GlobalContext,
+};
+use semver::{Version, VersionReq};
 use tempfile::TempDir;
 
 use crate::error::{Result, RuskelError};
@@ -16,6 +20,120 @@ fn join_components(components: &[String]) -> String {
     components.join("::")
 }
 
+/// The name of the source that replaces crates.io when a local source is configured.
+const REPLACEMENT_SOURCE: &str = "ruskel-local";
+
+/// A local source that replaces crates.io, for fully offline operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceReplacement {
+    /// A directory created by `cargo vendor`.
+    Vendor(PathBuf),
+    /// A local registry directory.
+    LocalRegistry(PathBuf),
+}
+
+/// Controls where cargo fetches dependencies from.
+#[derive(Debug, Clone, Default)]
+pub struct SourceConfig {
+    /// Prevents cargo from accessing the network.
+    pub offline: bool,
+    /// An alternative registry, as configured in `.cargo/config.toml`, that crates named in a
+    /// target are fetched from.
+    pub registry: Option<String>,
+    /// A local source to use in place of crates.io.
+    pub replacement: Option<SourceReplacement>,
+}
+
+impl SourceConfig {
+    /// Returns the `--config` values that apply this configuration to cargo.
+    pub fn cargo_config(&self) -> Vec<String> {
+        let (key, path) = match &self.replacement {
+            None => return Vec::new(),
+            Some(SourceReplacement::Vendor(path)) => ("directory", path),
+            Some(SourceReplacement::LocalRegistry(path)) => ("local-registry", path),
+        };
+        let path = absolute(path).unwrap_or_else(|_| path.clone());
+        vec![
+            format!("source.crates-io.replace-with={:?}", REPLACEMENT_SOURCE),
+            format!(
+                "source.{}.{}={:?}",
+                REPLACEMENT_SOURCE,
+                key,
+                path.display().to_string()
+            ),
+        ]
+    }
+}
+
+/// The features to enable on a package fetched as a dependency.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureConfig {
+    pub no_default_features: bool,
+    pub all_features: bool,
+    pub features: Vec<String>,
+}
+
+/// A package found in a dependency graph.
+#[derive(Debug)]
+pub struct FoundDependency {
+    pub path: CargoPath,
+    pub version: Version,
+    /// All features the package defines, including implicit features for optional dependencies.
+    pub features: Vec<String>,
+}
+
+/// A workspace member with a library target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceLib {
+    /// The package name.
+    pub name: String,
+    /// The name of the library crate, as it appears in paths.
+    pub crate_name: String,
+    /// The crate names of the other workspace libraries this one depends on, excluding dev and
+    /// build dependencies.
+    pub dependencies: Vec<String>,
+}
+
+/// A git repository to fetch a package from, at an optional revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSource {
+    pub url: String,
+    pub rev: Option<String>,
+}
+
+/// Parses a git target of the form `git+<url>[#<rev>][::<package>[::module::path]]`. Bare
+/// `file://` URLs are also accepted, for local repositories. If no package is named, the
+/// repository name is used. Returns `None` if the target isn't a git target.
+pub fn parse_git_target(target: &str) -> Option<(GitSource, Vec<String>)> {
+    let target = if let Some(rest) = target.strip_prefix("git+") {
+        rest
+    } else if target.starts_with("file://") {
+        target
+    } else {
+        return None;
+    };
+
+    let mut parts = target.split("::");
+    let source = parts.next()?;
+    let mut components: Vec<String> = parts.map(|x| x.into()).collect();
+
+    let (url, rev) = match source.split_once('#') {
+        Some((url, rev)) => (url, Some(rev.to_string())),
+        None => (source, None),
+    };
+    if components.is_empty() {
+        let name = url.trim_end_matches('/').rsplit('/').next()?;
+        components.push(name.trim_end_matches(".git").to_string());
+    }
+    Some((
+        GitSource {
+            url: url.to_string(),
+            rev,
+        },
+        components,
+    ))
+}
+
 #[derive(Debug)]
 pub enum CargoPath {
     Path(PathBuf),
@@ -51,6 +169,18 @@ impl CargoPath {
         self.has_manifest() && !self.is_workspace()
     }
 
+    /// Returns the name of the package's library crate, as it appears in item paths.
+    pub fn crate_name(&self) -> Result<String> {
+        let manifest = cargo_toml::Manifest::from_path(self.manifest_path())
+            .map_err(|e| RuskelError::ManifestParse(e.to_string()))?;
+        let name = manifest
+            .lib
+            .and_then(|lib| lib.name)
+            .or_else(|| manifest.package.map(|p| p.name))
+            .ok_or_else(|| RuskelError::ManifestParse("no package name".to_string()))?;
+        Ok(name.replace('-', "_"))
+    }
+
     pub fn is_workspace(&self) -> bool {
         if !self.has_manifest() {
             return false;
@@ -60,15 +190,63 @@ impl CargoPath {
             .ok();
         manifest
             .as_ref()
-            .map_or(false, |m| m.workspace.is_some() && m.package.is_none())
+            .is_some_and(|m| m.workspace.is_some() && m.package.is_none())
     }
 
     pub fn create_dummy_crate(
         &self,
         dependency: &str,
         version: Option<String>,
-        features: Option<&[&str]>,
+        features: &[String],
+        default_features: bool,
+        registry: Option<&str>,
     ) -> Result<()> {
+        let version_str = version.map_or("*".to_string(), |v| v.to_string());
+        let registry_str = registry.map_or(String::new(), |r| format!(", registry = {:?}", r));
+        self.write_dummy_crate(
+            dependency,
+            &format!("version = \"{}\"{}", version_str, registry_str),
+            features,
+            default_features,
+        )
+    }
+
+    /// Creates a dummy crate that depends on a package fetched from a git repository.
+    pub fn create_git_dummy_crate(
+        &self,
+        dependency: &str,
+        git: &GitSource,
+        features: &[String],
+        default_features: bool,
+    ) -> Result<()> {
+        let rev_str = git
+            .rev
+            .as_ref()
+            .map_or(String::new(), |r| format!(", rev = {:?}", r));
+        self.write_dummy_crate(
+            dependency,
+            &format!("git = {:?}{}", git.url, rev_str),
+            features,
+            default_features,
+        )
+    }
+
+    /// Writes a dummy crate whose only dependency is `dependency`, declared with the given
+    /// inline table contents and feature selection.
+    fn write_dummy_crate(
+        &self,
+        dependency: &str,
+        spec: &str,
+        features: &[String],
+        default_features: bool,
+    ) -> Result<()> {
+        let mut spec = spec.to_string();
+        if !features.is_empty() {
+            spec.push_str(&format!(", features = {:?}", features));
+        }
+        if !default_features {
+            spec.push_str(", default-features = false");
+        }
         if self.has_manifest() {
             return Err(RuskelError::Cargo("manifest already exists".to_string()));
         }
@@ -80,23 +258,76 @@ impl CargoPath {
         writeln!(file, "// Dummy crate")?;
 
         let manifest_path = self.manifest_path();
-        let version_str = version.map_or("*".to_string(), |v| v.to_string());
-        let features_str = features.map_or(String::new(), |f| format!(", features = {:?}", f));
         let manifest = format!(
             r#"[package]
             name = "dummy-crate"
             version = "0.1.0"
+            edition = "2021"
 
             [dependencies]
-            {} = {{ version = "{}"{}}}
+            {} = {{ {} }}
             "#,
-            dependency, version_str, features_str
+            dependency, spec
         );
         fs::write(manifest_path, manifest)?;
         Ok(())
     }
 
-    pub fn find_dependency(&self, dependency: &str, offline: bool) -> Result<Option<CargoPath>> {
+    /// Returns the root of the workspace the package belongs to, which is the directory cargo
+    /// runs rustdoc from.
+    pub fn workspace_root(&self) -> Result<PathBuf> {
+        let config = GlobalContext::default().map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let workspace = Workspace::new(&self.manifest_path(), &config)
+            .map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        Ok(workspace.root().to_path_buf())
+    }
+
+    /// Returns the workspace's members that have a library target, in workspace order.
+    pub fn workspace_libs(&self) -> Result<Vec<WorkspaceLib>> {
+        let config = GlobalContext::default().map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let workspace = Workspace::new(&self.manifest_path(), &config)
+            .map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let libs: Vec<(String, String)> = workspace
+            .members()
+            .filter_map(|p| {
+                let lib = p.targets().iter().find(|t| t.is_lib())?;
+                Some((p.name().to_string(), lib.crate_name()))
+            })
+            .collect();
+
+        Ok(workspace
+            .members()
+            .filter_map(|p| {
+                let (name, crate_name) = libs.iter().find(|(name, _)| name == p.name().as_str())?;
+                let dependencies = p
+                    .dependencies()
+                    .iter()
+                    .filter(|d| d.kind() == DepKind::Normal)
+                    .filter_map(|d| {
+                        libs.iter()
+                            .find(|(name, _)| name == d.package_name().as_str())
+                            .map(|(_, crate_name)| crate_name.clone())
+                    })
+                    .collect();
+                Some(WorkspaceLib {
+                    name: name.clone(),
+                    crate_name: crate_name.clone(),
+                    dependencies,
+                })
+            })
+            .collect())
+    }
+
+    /// Finds a dependency in the package's resolved dependency graph, using its lockfile if
+    /// there is one, and downloads it if needed. Versions the current package depends on directly
+    /// are preferred, and `version` narrows the candidates further. Workspace members are never
+    /// matched. Returns an error listing the candidates if several versions remain.
+    pub fn find_dependency(
+        &self,
+        dependency: &str,
+        version: Option<&VersionReq>,
+        sources: &SourceConfig,
+    ) -> Result<Option<FoundDependency>> {
         let mut config = GlobalContext::default().map_err(|e| RuskelError::Cargo(e.to_string()))?;
         config
             .configure(
@@ -105,33 +336,65 @@ impl CargoPath {
                 None,  // color
                 false, // frozen
                 false, // locked
-                offline,
+                sources.offline,
                 &None, // target_dir
                 &[],   // unstable_flags
-                &[],   // cli_config
+                &sources.cargo_config(),
             )
             .map_err(|e| RuskelError::Cargo(e.to_string()))?;
         let workspace = Workspace::new(&self.manifest_path(), &config)
             .map_err(|e| RuskelError::Cargo(e.to_string()))?;
 
-        let (_, ps) = ops::fetch(
-            &workspace,
-            &ops::FetchOptions {
-                gctx: &config,
-                targets: vec![],
-            },
-        )
-        .map_err(|e| RuskelError::Cargo(e.to_string()))?;
-
-        for package in ps.packages() {
-            if package.name().as_str() == dependency {
-                return Ok(Some(CargoPath::Path(
-                    package.manifest_path().parent().unwrap().to_path_buf(),
-                )));
+        let (packages, resolve) =
+            ops::resolve_ws(&workspace).map_err(|e| RuskelError::Cargo(e.to_string()))?;
+
+        let mut candidates: Vec<PackageId> = resolve
+            .iter()
+            .filter(|id| id.name().as_str() == dependency)
+            .filter(|id| version.is_none_or(|req| req.matches(id.version())))
+            .filter(|id| !workspace.members().any(|m| m.package_id() == *id))
+            .collect();
+        if let Ok(current) = workspace.current() {
+            let direct: Vec<PackageId> = resolve
+                .deps(current.package_id())
+                .map(|(id, _)| id)
+                .filter(|id| candidates.contains(id))
+                .collect();
+            if !direct.is_empty() {
+                candidates = direct;
             }
         }
-
-        Ok(None)
+        candidates.sort();
+        candidates.dedup();
+
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [id] => {
+                let package = packages
+                    .get_one(*id)
+                    .map_err(|e| RuskelError::Cargo(e.to_string()))?;
+                Ok(Some(FoundDependency {
+                    path: CargoPath::Path(package.manifest_path().parent().unwrap().to_path_buf()),
+                    version: id.version().clone(),
+                    features: package
+                        .summary()
+                        .features()
+                        .keys()
+                        .map(|f| f.to_string())
+                        .collect(),
+                }))
+            }
+            _ => Err(RuskelError::AmbiguousDependency(format!(
+                "{} matches several packages in the dependency graph: {}; select one with {}@<version>",
+                dependency,
+                candidates
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                dependency
+            ))),
+        }
     }
 
     pub fn nearest_manifest(start_dir: &Path) -> Option<CargoPath> {
@@ -166,6 +429,8 @@ impl CargoPath {
                 return Ok(Some(ResolvedTarget {
                     package_path: CargoPath::Path(package_path),
                     filter: String::new(),
+                    dependent: None,
+                    dependency: None,
                 }));
             }
         }
@@ -177,6 +442,8 @@ impl CargoPath {
             return Ok(Some(ResolvedTarget {
                 package_path: self.copy()?,
                 filter: components.join("::"),
+                dependent: None,
+                dependency: None,
             }));
         } else if self.is_workspace() {
             if components.is_empty() {
@@ -205,7 +472,7 @@ impl CargoPath {
     ///     - "module::path" matches a module and subpath in the package
     ///     - "package" matches a dependency in the workspace
     /// - Otherwise, the first component is retreived from cargo.io
-    pub fn from_target(target: &str) -> Result<ResolvedTarget> {
+    pub fn from_target(target: &str, sources: &SourceConfig) -> Result<ResolvedTarget> {
         let components: Vec<String> = target.split("::").map(|x| x.into()).collect();
         if components.is_empty() {
             return Err(RuskelError::ModuleNotFound("empty target".to_string()));
@@ -213,7 +480,11 @@ impl CargoPath {
 
         if is_path(&components[0]) {
             let root = CargoPath::Path(components[0].clone().into());
-            let subpath = components[1..].to_vec();
+            let mut subpath = components[1..].to_vec();
+            // Item paths start with the crate name, which is implied by a package path
+            if root.is_package() && !subpath.is_empty() {
+                subpath.insert(0, root.crate_name()?);
+            }
             if let Some(resolved) = root.search_spec(&subpath)? {
                 return Ok(resolved);
             } else if components.len() == 1 {
@@ -237,10 +508,12 @@ impl CargoPath {
         }
 
         let dummy = CargoPath::TempDir(TempDir::new()?);
-        dummy.create_dummy_crate(&components[0], None, None)?;
+        dummy.create_dummy_crate(&components[0], None, &[], true, sources.registry.as_deref())?;
         Ok(ResolvedTarget {
             package_path: dummy,
             filter: components.join("::"),
+            dependent: None,
+            dependency: None,
         })
     }
 }
@@ -249,42 +522,131 @@ impl CargoPath {
 pub struct ResolvedTarget {
     pub package_path: CargoPath,
     pub filter: String,
+    /// A dummy crate that depends on the package. When set, the package is documented as a
+    /// dependency of this crate, so only the dependencies it activates need to be available.
+    pub dependent: Option<CargoPath>,
+    /// The name and version of the package, when the target names a dependency or a crate fetched
+    /// from a registry rather than a local package.
+    pub dependency: Option<(String, Version)>,
 }
 
-pub fn resolve_target(target: &str, offline: bool) -> Result<ResolvedTarget> {
-    let (target_str, version) = parse_target(target)?;
+/// Resolves a target, looking for versioned targets among the dependencies of the project
+/// containing `project_dir`.
+pub fn resolve_target(
+    target: &str,
+    project_dir: &Path,
+    sources: &SourceConfig,
+    features: &FeatureConfig,
+) -> Result<ResolvedTarget> {
+    // Git URLs may contain '@', so they're recognised before version parsing
+    let git_target = parse_git_target(target);
+    let (target_str, version) = if git_target.is_some() {
+        (target.to_string(), None)
+    } else {
+        parse_target(target)?
+    };
 
-    let mut resolved = if version.is_some() {
-        // If a version is specified, always create a dummy package
+    let mut resolved = if let Some((git, components)) = &git_target {
         let dummy = CargoPath::TempDir(TempDir::new()?);
-        let components: Vec<String> = target_str.split("::").map(|x| x.into()).collect();
-        dummy.create_dummy_crate(
-            &components[0],
-            version.as_ref().map(|v| v.to_string()),
-            None,
-        )?;
-        let filter = components.join("::");
+        dummy.create_git_dummy_crate(&components[0], git, &[], true)?;
         ResolvedTarget {
             package_path: dummy,
-            filter,
+            filter: components.join("::"),
+            dependent: None,
+            dependency: None,
+        }
+    } else if let Some(req) = &version {
+        // A versioned target is looked for in the current project's dependencies first, and
+        // fetched from the registry otherwise. `latest` always means the newest release, not
+        // whatever the project happens to lock.
+        let components: Vec<String> = target_str.split("::").map(|x| x.into()).collect();
+        let project = if *req == VersionReq::STAR {
+            None
+        } else {
+            CargoPath::nearest_manifest(project_dir)
+        };
+        let package_path = match project {
+            Some(root) => root,
+            None => registry_dummy(&components[0], req, sources)?,
+        };
+        ResolvedTarget {
+            package_path,
+            filter: components.join("::"),
+            dependent: None,
+            dependency: None,
         }
     } else {
-        CargoPath::from_target(&target_str)?
+        CargoPath::from_target(&target_str, sources)?
     };
 
     if !resolved.filter.is_empty() {
         let first_component = resolved.filter.split("::").next().unwrap().to_string();
-        if let Some(cp) = resolved
-            .package_path
-            .find_dependency(&first_component, offline)?
-        {
-            resolved.package_path = cp;
+        let mut found =
+            resolved
+                .package_path
+                .find_dependency(&first_component, version.as_ref(), sources)?;
+        if let Some(req) = version.as_ref().filter(|_| found.is_none()) {
+            if !matches!(resolved.package_path, CargoPath::TempDir(_)) {
+                resolved.package_path = registry_dummy(&first_component, req, sources)?;
+                found =
+                    resolved
+                        .package_path
+                        .find_dependency(&first_component, Some(req), sources)?;
+            }
+        }
+        if let Some(found) = found {
+            let dummy = std::mem::replace(&mut resolved.package_path, found.path);
+            // Fetched packages are documented through a dummy crate, so they build just as they
+            // would as a dependency, with the features selected in its declaration. The features a
+            // package defines are only known once it's resolved, so that crate is generated here,
+            // pinned to the resolved version.
+            if matches!(dummy, CargoPath::TempDir(_)) {
+                let selected = if features.all_features {
+                    &found.features
+                } else {
+                    &features.features
+                };
+                let dependent = CargoPath::TempDir(TempDir::new()?);
+                match &git_target {
+                    Some((git, _)) => dependent.create_git_dummy_crate(
+                        &first_component,
+                        git,
+                        selected,
+                        !features.no_default_features,
+                    )?,
+                    None => dependent.create_dummy_crate(
+                        &first_component,
+                        Some(format!("={}", found.version)),
+                        selected,
+                        !features.no_default_features,
+                        sources.registry.as_deref(),
+                    )?,
+                }
+                resolved.dependent = Some(dependent);
+            }
+            resolved.dependency = Some((first_component, found.version));
         }
     }
     Ok(resolved)
 }
 
-pub fn parse_target(target: &str) -> Result<(String, Option<Version>)> {
+/// Creates a dummy crate that depends on the given version of a registry crate.
+fn registry_dummy(name: &str, version: &VersionReq, sources: &SourceConfig) -> Result<CargoPath> {
+    let dummy = CargoPath::TempDir(TempDir::new()?);
+    dummy.create_dummy_crate(
+        name,
+        Some(version.to_string()),
+        &[],
+        true,
+        sources.registry.as_deref(),
+    )?;
+    Ok(dummy)
+}
+
+/// Splits a target into its path and an optional version requirement after `@`. An exact
+/// version like `1.2.3` selects only that version, rather than being treated as `^1.2.3` as in
+/// Cargo.toml, and `latest` selects the newest release.
+pub fn parse_target(target: &str) -> Result<(String, Option<VersionReq>)> {
     let parts: Vec<&str> = target.rsplitn(2, '@').collect();
 
     match parts.len() {
@@ -295,9 +657,10 @@ pub fn parse_target(target: &str) -> Result<(String, Option<Version>)> {
                     "Target contains multiple '@' characters".to_string(),
                 ));
             }
-            let version = Version::parse(parts[0].trim())
-                .map_err(|e| RuskelError::InvalidVersion(e.to_string()))?;
-            Ok((parts[1].to_string(), Some(version)))
+            Ok((
+                parts[1].to_string(),
+                Some(parse_version_req(parts[0].trim())?),
+            ))
         }
         _ => Err(RuskelError::InvalidTarget(
             "Invalid target format".to_string(),
@@ -305,27 +668,53 @@ pub fn parse_target(target: &str) -> Result<(String, Option<Version>)> {
     }
 }
 
+fn parse_version_req(version: &str) -> Result<VersionReq> {
+    if version == "latest" {
+        return Ok(VersionReq::STAR);
+    }
+    if let Ok(exact) = Version::parse(version) {
+        return VersionReq::parse(&format!("={}", exact))
+            .map_err(|e| RuskelError::InvalidVersion(e.to_string()));
+    }
+    VersionReq::parse(version).map_err(|e| RuskelError::InvalidVersion(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::tempdir;
 
+    fn req(s: &str) -> VersionReq {
+        VersionReq::parse(s).unwrap()
+    }
+
     #[test]
     fn test_parse_target() -> Result<()> {
         let test_cases = vec![
             ("my/target::here", Ok(("my/target::here".to_string(), None))),
             (
                 "my/target::here@1.2.3",
-                Ok(("my/target::here".to_string(), Some(Version::new(1, 2, 3)))),
+                Ok(("my/target::here".to_string(), Some(req("=1.2.3")))),
             ),
             (
                 "my/target::here@1.2.3-alpha.1+build.456",
                 Ok((
                     "my/target::here".to_string(),
-                    Some(Version::parse("1.2.3-alpha.1+build.456").unwrap()),
+                    Some(req("=1.2.3-alpha.1+build.456")),
                 )),
             ),
+            ("serde@^1.0", Ok(("serde".to_string(), Some(req("^1.0"))))),
+            ("tokio@~1.38", Ok(("tokio".to_string(), Some(req("~1.38"))))),
+            (
+                "foo@latest",
+                Ok(("foo".to_string(), Some(VersionReq::STAR))),
+            ),
+            (
+                "foo@>=0.3, <0.5",
+                Ok(("foo".to_string(), Some(req(">=0.3, <0.5")))),
+            ),
+            ("foo@1", Ok(("foo".to_string(), Some(req("^1"))))),
             ("my/target::here@invalid", Err("Invalid version:")),
             ("my/target::here@1.0.0@2.0.0", Err("Invalid target:")),
             ("my/target::here@", Err("Invalid version:")),
@@ -355,12 +744,106 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_resolve_versioned_target_in_project() -> Result<()> {
+        let project = tempdir()?;
+        fs::write(
+            project.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "project"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+semver = "=1.0.23"
+"#,
+        )?;
+        fs::create_dir(project.path().join("src"))?;
+        fs::write(project.path().join("src/lib.rs"), "")?;
+        let sources = SourceConfig {
+            offline: true,
+            ..Default::default()
+        };
+        let features = FeatureConfig::default();
+        let resolve = |target: &str| resolve_target(target, project.path(), &sources, &features);
+
+        // An explicit requirement uses the version the project locks
+        let resolved = resolve("semver@1")?;
+        assert!(resolved.dependent.is_none());
+        assert_eq!(resolved.dependency.unwrap().1, Version::new(1, 0, 23));
+        // `latest` ignores the project and is fetched from the registry. Which release that is
+        // depends on the local registry cache when offline.
+        let resolved = resolve("semver@latest")?;
+        assert!(resolved.dependent.is_some());
+        assert!(VersionReq::STAR.matches(&resolved.dependency.unwrap().1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_git_target() {
+        let git = |url: &str, rev: Option<&str>| GitSource {
+            url: url.to_string(),
+            rev: rev.map(|r| r.to_string()),
+        };
+        let test_cases = vec![
+            (
+                "git+https://host/repo.git",
+                Some((git("https://host/repo.git", None), vec!["repo"])),
+            ),
+            (
+                "git+https://host/repo.git#v1.0::mycrate::module",
+                Some((
+                    git("https://host/repo.git", Some("v1.0")),
+                    vec!["mycrate", "module"],
+                )),
+            ),
+            (
+                "git+ssh://git@host/org/repo#abc123",
+                Some((git("ssh://git@host/org/repo", Some("abc123")), vec!["repo"])),
+            ),
+            (
+                "file:///src/repo/::mycrate",
+                Some((git("file:///src/repo/", None), vec!["mycrate"])),
+            ),
+            ("serde::de", None),
+            ("./path/to/crate::module", None),
+        ];
+
+        for (input, expected) in test_cases {
+            let expected = expected.map(|(g, c)| (g, c.iter().map(|x| x.to_string()).collect()));
+            assert_eq!(parse_git_target(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_create_git_dummy_crate() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cargo_path = CargoPath::Path(temp_dir.path().to_path_buf());
+
+        cargo_path.create_git_dummy_crate(
+            "mycrate",
+            &GitSource {
+                url: "https://host/repo.git".to_string(),
+                rev: Some("main".to_string()),
+            },
+            &[],
+            true,
+        )?;
+        let manifest_content = fs::read_to_string(cargo_path.manifest_path())?;
+        assert!(manifest_content
+            .contains(r#"mycrate = { git = "https://host/repo.git", rev = "main" }"#));
+
+        Ok(())
+    }
+
     #[test]
     fn test_create_dummy_crate() -> Result<()> {
         let temp_dir = tempdir()?;
         let cargo_path = CargoPath::Path(temp_dir.path().to_path_buf());
 
-        cargo_path.create_dummy_crate("serde", None, None)?;
+        cargo_path.create_dummy_crate("serde", None, &[], true, None)?;
         assert!(cargo_path.has_manifest());
 
         let manifest_content = fs::read_to_string(cargo_path.manifest_path())?;
@@ -369,11 +852,73 @@ mod tests {
         assert!(manifest_content.contains("serde = { version = \"*\""));
 
         // Ensure creating a second crate fails
-        assert!(cargo_path.create_dummy_crate("rand", None, None).is_err());
+        assert!(cargo_path
+            .create_dummy_crate("rand", None, &[], true, None)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_dummy_crate_registry() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cargo_path = CargoPath::Path(temp_dir.path().to_path_buf());
+
+        cargo_path.create_dummy_crate("serde", None, &[], true, Some("mirror"))?;
+        let manifest_content = fs::read_to_string(cargo_path.manifest_path())?;
+        assert!(manifest_content.contains(r#"serde = { version = "*", registry = "mirror" }"#));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_dummy_crate_features() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let cargo_path = CargoPath::Path(temp_dir.path().to_path_buf());
+        cargo_path.create_dummy_crate(
+            "tokio",
+            Some("^1".to_string()),
+            &["full".to_string(), "test-util".to_string()],
+            false,
+            None,
+        )?;
+        let manifest_content = fs::read_to_string(cargo_path.manifest_path())?;
+        assert!(manifest_content.contains(
+            r#"tokio = { version = "^1", features = ["full", "test-util"], default-features = false }"#
+        ));
+        assert!(cargo_toml::Manifest::from_str(&manifest_content).is_ok());
 
         Ok(())
     }
 
+    #[test]
+    fn test_source_config() {
+        assert!(SourceConfig::default().cargo_config().is_empty());
+
+        let sources = SourceConfig {
+            replacement: Some(SourceReplacement::Vendor(PathBuf::from("/src/vendor"))),
+            ..Default::default()
+        };
+        assert_eq!(
+            sources.cargo_config(),
+            vec![
+                r#"source.crates-io.replace-with="ruskel-local""#,
+                r#"source.ruskel-local.directory="/src/vendor""#,
+            ]
+        );
+
+        let sources = SourceConfig {
+            replacement: Some(SourceReplacement::LocalRegistry(PathBuf::from(
+                "/src/registry",
+            ))),
+            ..Default::default()
+        };
+        assert_eq!(
+            sources.cargo_config()[1],
+            r#"source.ruskel-local.local-registry="/src/registry""#
+        );
+    }
+
     #[test]
     fn test_is_workspace() -> Result<()> {
         let temp_dir = tempdir()?;
@@ -462,6 +1007,80 @@ mod tests {
         Ok(())
     }
 
+    fn write_package(dir: &Path, name: &str, version: &str, deps: &[(&str, &str)]) -> Result<()> {
+        fs::create_dir_all(dir.join("src"))?;
+        fs::write(dir.join("src").join("lib.rs"), "")?;
+        let deps: String = deps
+            .iter()
+            .map(|(name, path)| format!("{} = {{ path = {:?} }}\n", name, path))
+            .collect();
+        fs::write(
+            dir.join("Cargo.toml"),
+            format!(
+                "[package]\nname = {:?}\nversion = {:?}\n\n[dependencies]\n{}",
+                name, version, deps
+            ),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_dependency() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let root = temp_dir.path();
+
+        // Two versions of "dep" in the graph, used by "old" and "new"
+        write_package(&root.join("dep1"), "dep", "0.1.0", &[])?;
+        write_package(&root.join("dep2"), "dep", "0.2.0", &[])?;
+        write_package(&root.join("old"), "old", "0.1.0", &[("dep", "../dep1")])?;
+        write_package(&root.join("new"), "new", "0.1.0", &[("dep", "../dep2")])?;
+        write_package(
+            &root.join("app"),
+            "app",
+            "0.1.0",
+            &[("old", "../old"), ("new", "../new")],
+        )?;
+        write_package(
+            &root.join("direct"),
+            "direct",
+            "0.1.0",
+            &[("dep", "../dep1"), ("new", "../new")],
+        )?;
+
+        let sources = SourceConfig {
+            offline: true,
+            ..Default::default()
+        };
+
+        // The version a package depends on directly is preferred
+        let direct = CargoPath::Path(root.join("direct"));
+        let found = direct.find_dependency("dep", None, &sources)?.unwrap();
+        assert_eq!(found.path.as_path(), root.join("dep1"));
+        assert_eq!(found.version, Version::new(0, 1, 0));
+
+        // Otherwise several versions are ambiguous, and the error lists them
+        let app = CargoPath::Path(root.join("app"));
+        let err = app.find_dependency("dep", None, &sources).unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("Ambiguous dependency:"), "{}", message);
+        assert!(message.contains("dep v0.1.0"), "{}", message);
+        assert!(message.contains("dep v0.2.0"), "{}", message);
+
+        // A version requirement disambiguates
+        let req = VersionReq::parse("0.2").unwrap();
+        let found = app.find_dependency("dep", Some(&req), &sources)?.unwrap();
+        assert_eq!(found.path.as_path(), root.join("dep2"));
+        assert_eq!(found.version, Version::new(0, 2, 0));
+
+        // Workspace members and unknown packages aren't dependencies
+        assert!(app.find_dependency("app", None, &sources)?.is_none());
+        assert!(app.find_dependency("missing", None, &sources)?.is_none());
+        let req = VersionReq::parse("0.3").unwrap();
+        assert!(app.find_dependency("dep", Some(&req), &sources)?.is_none());
+
+        Ok(())
+    }
+
     #[test]
     fn test_from_target() -> Result<()> {
         let temp_dir = tempdir()?;
@@ -509,18 +1128,33 @@ mod tests {
         )?;
 
         // Test resolving a package in the workspace
-        let resolved =
-            CargoPath::from_target(&format!("{}::pkg1::module", workspace_path.display()))?;
+        let sources = SourceConfig::default();
+        let resolved = CargoPath::from_target(
+            &format!("{}::pkg1::module", workspace_path.display()),
+            &sources,
+        )?;
         assert_eq!(resolved.package_path.as_path(), workspace_path.join("pkg1"));
         assert_eq!(resolved.filter, "pkg1::module");
 
         // Test resolving another package in the workspace
-        let resolved = CargoPath::from_target(&format!("{}::pkg2", workspace_path.display()))?;
+        let resolved =
+            CargoPath::from_target(&format!("{}::pkg2", workspace_path.display()), &sources)?;
         assert_eq!(resolved.package_path.as_path(), workspace_path.join("pkg2"));
         assert_eq!(resolved.filter, "pkg2");
 
+        // Test resolving a module within a package, which implies the crate name
+        let resolved = CargoPath::from_target(
+            &format!("{}::module", workspace_path.join("pkg1").display()),
+            &sources,
+        )?;
+        assert_eq!(resolved.package_path.as_path(), workspace_path.join("pkg1"));
+        assert_eq!(resolved.filter, "pkg1::module");
+
         // Test resolving a non-existent package
-        let result = CargoPath::from_target(&format!("{}::non_existent", workspace_path.display()));
+        let result = CargoPath::from_target(
+            &format!("{}::non_existent", workspace_path.display()),
+            &sources,
+        );
         assert!(result.is_err());
 
         Ok(())
@@ -0,0 +1,162 @@
+//! Documentation coverage of a crate's public surface.
+
+use std::fmt;
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, StructKind};
+
+use crate::render::Renderer;
+use crate::stats::write_table;
+use crate::surface::Surface;
+
+/// Documentation coverage for one module: the module itself, its items, and their fields,
+/// variants, methods and trait items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleCoverage {
+    pub path: String,
+    pub documented: usize,
+    pub total: usize,
+    /// Paths of the items without docs, e.g. `mycrate::Config::timeout`.
+    pub missing: Vec<String>,
+}
+
+impl ModuleCoverage {
+    fn check(&mut self, path: String, item: &Item) {
+        self.total += 1;
+        if item
+            .docs
+            .as_deref()
+            .is_some_and(|docs| !docs.trim().is_empty())
+        {
+            self.documented += 1;
+        } else {
+            self.missing.push(path);
+        }
+    }
+}
+
+/// Documentation coverage for each public module of a crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocCoverage {
+    /// Modules in the order they're declared, starting with the crate root.
+    pub modules: Vec<ModuleCoverage>,
+}
+
+impl DocCoverage {
+    /// Checks the docs of everything on the crate's public surface, or of all items if
+    /// `private_items` is set. Re-exports aren't checked themselves, but items only public
+    /// through a re-export are checked where they're re-exported.
+    pub fn new(crate_data: &Crate, private_items: bool) -> Self {
+        let renderer = Renderer::default().with_private_items(private_items);
+        let surface = Surface::new(crate_data, &renderer);
+        let mut modules = Vec::new();
+        for module in &surface.modules {
+            let mut coverage = ModuleCoverage {
+                path: module.path.clone(),
+                documented: 0,
+                total: 0,
+                missing: Vec::new(),
+            };
+            coverage.check(module.path.clone(), module.module);
+            for &item in &module.items {
+                if matches!(item.inner, ItemEnum::Module(_) | ItemEnum::Import(_)) {
+                    continue;
+                }
+                let Some(name) = &item.name else {
+                    continue;
+                };
+                let path = format!("{}::{}", module.path, name);
+                coverage.check(path.clone(), item);
+
+                let fields: Vec<&Id> = match &item.inner {
+                    ItemEnum::Struct(struct_) => match &struct_.kind {
+                        StructKind::Plain { fields, .. } => fields.iter().collect(),
+                        StructKind::Unit | StructKind::Tuple(_) => Vec::new(),
+                    },
+                    ItemEnum::Union(union_) => union_.fields.iter().collect(),
+                    ItemEnum::Enum(enum_) => enum_.variants.iter().collect(),
+                    _ => Vec::new(),
+                };
+                let fields = fields
+                    .into_iter()
+                    .filter_map(|id| crate_data.index.get(id))
+                    .filter(|field| match field.inner {
+                        ItemEnum::Variant(_) => !surface.is_hidden(field),
+                        _ => surface.included(field),
+                    });
+                for member in fields.chain(surface.members(item)) {
+                    if let Some(member_name) = &member.name {
+                        coverage.check(format!("{}::{}", path, member_name), member);
+                    }
+                }
+            }
+            modules.push(coverage);
+        }
+        DocCoverage { modules }
+    }
+
+    /// Keeps only the module at `path` and the modules below it. Returns None if there is no
+    /// such module.
+    pub fn select(self, path: &str) -> Option<Self> {
+        let prefix = format!("{}::", path);
+        let modules: Vec<ModuleCoverage> = self
+            .modules
+            .into_iter()
+            .filter(|m| m.path == path || m.path.starts_with(&prefix))
+            .collect();
+        (!modules.is_empty()).then_some(DocCoverage { modules })
+    }
+
+    pub fn documented(&self) -> usize {
+        self.modules.iter().map(|m| m.documented).sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.modules.iter().map(|m| m.total).sum()
+    }
+
+    /// The percentage of items that are documented, or 100 if there are none.
+    pub fn percent(&self) -> f64 {
+        percent(self.documented(), self.total())
+    }
+}
+
+fn percent(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
+
+/// Renders a table with a row per module, followed by the items missing docs.
+impl fmt::Display for DocCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = vec![["module", "documented", "total", "coverage"]
+            .map(String::from)
+            .to_vec()];
+        let totals = [("total".to_string(), self.documented(), self.total())];
+        for (path, documented, total) in self
+            .modules
+            .iter()
+            .map(|m| (m.path.clone(), m.documented, m.total))
+            .chain(totals)
+        {
+            rows.push(vec![
+                path,
+                documented.to_string(),
+                total.to_string(),
+                format!("{:.1}%", percent(documented, total)),
+            ]);
+        }
+        write_table(f, &rows)?;
+
+        let missing: Vec<&String> = self.modules.iter().flat_map(|m| &m.missing).collect();
+        if !missing.is_empty() {
+            writeln!(f, "\nMissing docs:")?;
+            for path in missing {
+                writeln!(f, "    {}", path)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use rustdoc_types::{
-    FnDecl, FunctionPointer, GenericArg, GenericArgs, GenericBound, GenericParamDef,
-    GenericParamDefKind, Generics, Item, ItemEnum, Path, PolyTrait, Term, TraitBoundModifier, Type,
-    TypeBinding, TypeBindingKind, Visibility, WherePredicate,
+    Crate, Enum, FnDecl, FunctionPointer, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Generics, Impl, Item, ItemEnum, Path, PolyTrait, Struct, Term,
+    TraitBoundModifier, Type, TypeBinding, TypeBindingKind, Union, Visibility, WherePredicate,
 };
 
 macro_rules! extract_item {
@@ -21,6 +21,7 @@ macro_rules! extract_item {
 
 pub(crate) use extract_item;
 
+/// Renders the doc comment and `#[deprecated]` attribute that precede an item.
 pub fn docs(item: &Item) -> String {
     let mut output = String::new();
     if let Some(docs) = &item.docs {
@@ -28,9 +29,75 @@ pub fn docs(item: &Item) -> String {
             output.push_str(&format!("/// {}\n", line));
         }
     }
+    output.push_str(&render_deprecation(item));
     output
 }
 
+/// Renders the item's `#[deprecated]` attribute, with a trailing newline, or nothing if it isn't
+/// deprecated.
+pub fn render_deprecation(item: &Item) -> String {
+    let Some(deprecation) = &item.deprecation else {
+        return String::new();
+    };
+    let args: Vec<String> = [("since", &deprecation.since), ("note", &deprecation.note)]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| format!("{} = {:?}", key, v)))
+        .collect();
+    if args.is_empty() {
+        "#[deprecated]\n".to_string()
+    } else {
+        format!("#[deprecated({})]\n", args.join(", "))
+    }
+}
+
+/// Is the item marked `#[doc(hidden)]`?
+pub fn is_doc_hidden(item: &Item) -> bool {
+    item.attrs.iter().any(|attr| {
+        let attr: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+        attr.strip_prefix("#[doc(")
+            .and_then(|a| a.strip_suffix(")]"))
+            .is_some_and(|args| args.split(',').any(|arg| arg == "hidden"))
+    })
+}
+
+/// Returns the impl blocks of a struct, enum or union, or nothing for other items. Impls are
+/// also listed on the types they mention, so they may be for another type.
+pub fn type_impls<'a>(
+    crate_data: &'a Crate,
+    item: &'a Item,
+) -> impl Iterator<Item = (&'a Item, &'a Impl)> {
+    let impls: &[_] = match &item.inner {
+        ItemEnum::Struct(Struct { impls, .. })
+        | ItemEnum::Enum(Enum { impls, .. })
+        | ItemEnum::Union(Union { impls, .. }) => impls,
+        _ => &[],
+    };
+    impls
+        .iter()
+        .filter_map(|id| crate_data.index.get(id))
+        .filter_map(|impl_item| match &impl_item.inner {
+            ItemEnum::Impl(impl_) => Some((impl_item, impl_)),
+            _ => None,
+        })
+}
+
+/// Returns the items of the inherent impls of a struct, enum or union, each with the impl block
+/// it's declared in.
+pub fn inherent_items<'a>(
+    crate_data: &'a Crate,
+    item: &'a Item,
+) -> impl Iterator<Item = (&'a Item, &'a Item)> {
+    type_impls(crate_data, item)
+        .filter(|(_, impl_)| impl_.trait_.is_none())
+        .flat_map(move |(impl_item, impl_)| {
+            impl_
+                .items
+                .iter()
+                .filter_map(|id| crate_data.index.get(id))
+                .map(move |member| (impl_item, member))
+        })
+}
+
 pub fn render_vis(item: &Item) -> String {
     match &item.visibility {
         Visibility::Public => "pub ".to_string(),
@@ -30,6 +30,14 @@ pub enum RuskelError {
     #[error("Filter '{0}' did not match any items")]
     FilterNotMatched(String),
 
+    /// The specified filter could not be parsed.
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+
+    /// An invalid rendering option was provided.
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
     /// Failed to parse a Cargo.toml manifest
     #[error("Failed to parse Cargo.toml manifest: {0}")]
     ManifestParse(String),
@@ -45,6 +53,18 @@ pub enum RuskelError {
     /// Indicates an invalid target specification was provided.
     #[error("Invalid target: {0}")]
     InvalidTarget(String),
+
+    /// Several versions of a dependency are in the dependency graph and none is preferred.
+    #[error("Ambiguous dependency: {0}")]
+    AmbiguousDependency(String),
+
+    /// The API differs from a stored snapshot.
+    #[error("API differs from snapshot {path}:\n{diff}")]
+    SnapshotMismatch { path: String, diff: String },
+
+    /// A snapshot file to check against does not exist.
+    #[error("Snapshot not found: {0}")]
+    SnapshotNotFound(String),
 }
 
 impl From<syntect::Error> for RuskelError {
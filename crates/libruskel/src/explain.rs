@@ -0,0 +1,240 @@
+//! Gathers everything related to a type from across a crate: its definition, its impls, the
+//! functions that use it and the traits bounded by it.
+
+use std::collections::HashSet;
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum};
+
+use crate::crateutils::inherent_items;
+use crate::error::{Result, RuskelError};
+use crate::render::Renderer;
+use crate::signature::signature_paths;
+
+/// Renders the type or trait at `path` followed by everything in the crate related to it, in
+/// sections headed by comments.
+pub fn explain(crate_data: &Crate, renderer: &Renderer, path: &str) -> Result<String> {
+    let (id, item) =
+        find_item(crate_data, path).ok_or_else(|| RuskelError::FilterNotMatched(path.into()))?;
+    let impls = match &item.inner {
+        ItemEnum::Struct(struct_) => &struct_.impls,
+        ItemEnum::Enum(enum_) => &enum_.impls,
+        ItemEnum::Union(union_) => &union_.impls,
+        ItemEnum::Trait(trait_) => &trait_.implementations,
+        ItemEnum::TypeAlias(_) => &Vec::new(),
+        _ => {
+            return Err(RuskelError::InvalidTarget(format!(
+                "'{}' is not a type or trait",
+                path
+            )))
+        }
+    };
+    let name = item.name.as_deref().unwrap_or(path);
+    let parent_path = path.rsplit_once("::").map_or("", |(parent, _)| parent);
+
+    let mut sections = vec![(
+        String::new(),
+        vec![renderer.render_fragment(crate_data, parent_path, item)?],
+    )];
+
+    let mut inherent = Vec::new();
+    let mut traits = Vec::new();
+    for impl_item in impls.iter().filter_map(|id| crate_data.index.get(id)) {
+        let ItemEnum::Impl(impl_) = &impl_item.inner else {
+            continue;
+        };
+        let rendered = renderer.render_fragment(crate_data, path, impl_item)?;
+        if rendered.is_empty() {
+            continue;
+        }
+        if impl_.trait_.is_some() {
+            traits.push(rendered);
+        } else {
+            inherent.push(rendered);
+        }
+    }
+    if matches!(item.inner, ItemEnum::Trait(_)) {
+        sections.push(("Implementations".to_string(), traits));
+    } else {
+        sections.push(("Inherent impls".to_string(), inherent));
+        sections.push(("Trait impls".to_string(), traits));
+    }
+
+    let (functions, bounded) = find_users(crate_data, renderer, id);
+    let mut rendered = Vec::new();
+    for (user_path, user) in functions {
+        let parent = user_path.rsplit_once("::").map_or("", |(parent, _)| parent);
+        let function = renderer.render_fragment(crate_data, parent, user)?;
+        if !function.is_empty() {
+            rendered.push(format!("// {}\n{}", user_path, function));
+        }
+    }
+    sections.push((format!("Functions that take or return {}", name), rendered));
+    let mut rendered = Vec::new();
+    for (user_path, user) in bounded {
+        let parent = user_path.rsplit_once("::").map_or("", |(parent, _)| parent);
+        let trait_ = renderer.render_fragment(crate_data, parent, user)?;
+        if !trait_.is_empty() {
+            rendered.push(format!("// {}\n{}", user_path, trait_));
+        }
+    }
+    sections.push((format!("Traits with {} in their bounds", name), rendered));
+
+    let mut output = String::new();
+    for (title, fragments) in sections {
+        if fragments.is_empty() {
+            continue;
+        }
+        if !title.is_empty() {
+            output.push_str(&format!("// {}\n\n", title));
+        }
+        output.push_str(&fragments.join("\n"));
+        output.push('\n');
+    }
+    Ok(output.trim_end().to_string() + "\n")
+}
+
+/// Finds the item at a path, which may go through re-exports.
+pub(crate) fn find_item<'a>(crate_data: &'a Crate, path: &str) -> Option<(&'a Id, &'a Item)> {
+    let defined = crate_data
+        .paths
+        .iter()
+        .find(|(_, summary)| summary.crate_id == 0 && summary.path.join("::") == path)
+        .and_then(|(id, _)| crate_data.index.get_key_value(id));
+    if defined.is_some() {
+        return defined;
+    }
+    let root = crate_data.index.get(&crate_data.root)?;
+    let mut visited = HashSet::new();
+    find_in_module(crate_data, root, root.name.as_deref()?, path, &mut visited)
+}
+
+fn find_in_module<'a>(
+    crate_data: &'a Crate,
+    module: &'a Item,
+    module_path: &str,
+    path: &str,
+    visited: &mut HashSet<&'a Id>,
+) -> Option<(&'a Id, &'a Item)> {
+    let ItemEnum::Module(module) = &module.inner else {
+        return None;
+    };
+    for id in &module.items {
+        let Some(item) = crate_data.index.get(id) else {
+            continue;
+        };
+        let (name, target) = match &item.inner {
+            ItemEnum::Import(import) => {
+                let Some(target) = import.id.as_ref() else {
+                    continue;
+                };
+                let Some(target) = crate_data.index.get_key_value(target) else {
+                    continue;
+                };
+                // Glob imports bring the target module's items into this one
+                if import.glob {
+                    if visited.insert(target.0) {
+                        let found =
+                            find_in_module(crate_data, target.1, module_path, path, visited);
+                        if found.is_some() {
+                            return found;
+                        }
+                    }
+                    continue;
+                }
+                (import.name.as_str(), target)
+            }
+            _ => match &item.name {
+                Some(name) => (name.as_str(), (id, item)),
+                None => continue,
+            },
+        };
+        let item_path = format!("{}::{}", module_path, name);
+        if item_path == path {
+            return Some(target);
+        }
+        if path.starts_with(&format!("{}::", item_path)) && visited.insert(target.0) {
+            let found = find_in_module(crate_data, target.1, &item_path, path, visited);
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+    None
+}
+
+/// Items with their paths.
+type PathedItems<'a> = Vec<(String, &'a Item)>;
+
+/// Finds the functions and methods whose signatures mention the item, other than its own, and
+/// the traits whose generics or supertraits do. Both are returned with their paths, sorted.
+fn find_users<'a>(
+    crate_data: &'a Crate,
+    renderer: &Renderer,
+    target: &Id,
+) -> (PathedItems<'a>, PathedItems<'a>) {
+    let uses = |item: &Item| signature_paths(crate_data, item).contains(target);
+    // The item's own methods are already shown in its inherent impls
+    let own: HashSet<&Id> = crate_data
+        .index
+        .get(target)
+        .map(|item| {
+            inherent_items(crate_data, item)
+                .map(|(_, member)| &member.id)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut functions = Vec::new();
+    let mut traits = Vec::new();
+    for (id, summary) in &crate_data.paths {
+        if summary.crate_id != 0 || id == target {
+            continue;
+        }
+        let Some(item) = crate_data
+            .index
+            .get(id)
+            .filter(|item| renderer.is_visible(item))
+        else {
+            continue;
+        };
+        let path = summary.path.join("::");
+        let members: Vec<&Item> = match &item.inner {
+            ItemEnum::Function(_) => {
+                if uses(item) {
+                    functions.push((path, item));
+                }
+                continue;
+            }
+            ItemEnum::Trait(trait_) => {
+                if uses(item) {
+                    traits.push((path.clone(), item));
+                }
+                trait_
+                    .items
+                    .iter()
+                    .filter_map(|id| crate_data.index.get(id))
+                    .collect()
+            }
+            ItemEnum::Struct(_) | ItemEnum::Enum(_) | ItemEnum::Union(_) => {
+                inherent_items(crate_data, item)
+                    .map(|(_, method)| method)
+                    .filter(|method| renderer.is_visible(method))
+                    .collect()
+            }
+            _ => continue,
+        };
+        for member in members
+            .into_iter()
+            .filter(|member| !own.contains(&member.id))
+        {
+            if matches!(member.inner, ItemEnum::Function(_)) && uses(member) {
+                if let Some(name) = &member.name {
+                    functions.push((format!("{}::{}", path, name), member));
+                }
+            }
+        }
+    }
+    functions.sort_by(|a, b| a.0.cmp(&b.0));
+    traits.sort_by(|a, b| a.0.cmp(&b.0));
+    (functions, traits)
+}
@@ -0,0 +1,485 @@
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+use rustdoc_types::{Item, ItemEnum};
+
+use crate::error::{Result, RuskelError};
+
+/// The relationship between an item path and a filter.
+#[derive(Debug, PartialEq)]
+pub enum FilterMatch {
+    /// The path matches the filter exactly.
+    Hit,
+    /// The path is an ancestor of something the filter could match.
+    Prefix,
+    /// The path is a descendant of something the filter matches.
+    Suffix,
+    /// The path is unrelated to the filter.
+    Miss,
+}
+
+#[derive(Debug)]
+enum Component {
+    /// `**` - matches any number of path components, including none.
+    AnyDepth,
+    /// A single path component, matched against a literal, glob or regex.
+    Single(Regex),
+}
+
+impl Component {
+    fn parse(s: &str) -> Result<Self> {
+        if s == "**" {
+            return Ok(Component::AnyDepth);
+        }
+        let re = if s.len() >= 2 && s.starts_with('/') && s.ends_with('/') {
+            format!("^(?:{})$", &s[1..s.len() - 1])
+        } else {
+            glob_to_regex(s)
+        };
+        Regex::new(&re)
+            .map(Component::Single)
+            .map_err(|e| RuskelError::InvalidFilter(format!("{}: {}", s, e)))
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+fn at_component_start(before: &str) -> bool {
+    let before = before.trim_end();
+    before.is_empty() || before.ends_with("::") || before.ends_with(',')
+}
+
+fn at_component_end(after: &str) -> bool {
+    let after = after.trim_start();
+    after.is_empty() || after.starts_with("::") || after.starts_with(',')
+}
+
+/// Splits `s` on `sep`, ignoring separators that appear inside a `/regex/` component.
+fn split_outside_regex<'a>(s: &'a str, sep: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut in_regex = false;
+    let mut start = 0;
+    let mut i = 0;
+    while i < s.len() {
+        let (before, rest) = s.split_at(i);
+        if let Some(after) = rest.strip_prefix('/') {
+            if (!in_regex && at_component_start(before)) || (in_regex && at_component_end(after)) {
+                in_regex = !in_regex;
+            }
+        } else if !in_regex && rest.starts_with(sep) {
+            parts.push(&s[start..i]);
+            i += sep.len();
+            start = i;
+            continue;
+        }
+        i += rest.chars().next().unwrap().len_utf8();
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+#[derive(Debug)]
+struct Pattern {
+    components: Vec<Component>,
+}
+
+#[derive(Default)]
+struct MatchState {
+    hit: bool,
+    prefix: bool,
+    suffix: bool,
+}
+
+impl Pattern {
+    fn parse(s: &str) -> Result<Self> {
+        let components = split_outside_regex(s.trim(), "::")
+            .into_iter()
+            .map(Component::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Pattern { components })
+    }
+
+    fn walk(pattern: &[Component], path: &[&str], state: &mut MatchState) {
+        match (pattern.first(), path.first()) {
+            (None, None) => state.hit = true,
+            (None, Some(_)) => state.suffix = true,
+            (Some(Component::AnyDepth), _) => {
+                Self::walk(&pattern[1..], path, state);
+                if !path.is_empty() {
+                    Self::walk(pattern, &path[1..], state);
+                }
+            }
+            (Some(_), None) => state.prefix = true,
+            (Some(Component::Single(re)), Some(component)) => {
+                if re.is_match(component) {
+                    Self::walk(&pattern[1..], &path[1..], state);
+                }
+            }
+        }
+    }
+}
+
+/// A set of path patterns used to select items from a crate.
+///
+/// A filter is a comma-separated list of patterns. Each pattern is a `::`-separated path, where
+/// every component is one of:
+///
+/// - a literal name, e.g. `serde::de::Deserialize`
+/// - a glob, where `*` matches any run of characters and `?` any single character, e.g.
+///   `serde::de::*Deserialize*`
+/// - `**`, which matches any number of path components, e.g. `tokio::**::spawn`
+/// - a regular expression delimited by slashes, e.g. `serde::/(de|ser)/::Error`
+#[derive(Debug)]
+pub struct Filter {
+    source: String,
+    patterns: Vec<Pattern>,
+}
+
+impl Filter {
+    pub fn parse(filter: &str) -> Result<Self> {
+        let patterns = split_outside_regex(filter, ",")
+            .into_iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(Pattern::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Filter {
+            source: filter.to_string(),
+            patterns,
+        })
+    }
+
+    /// Prefixes each pattern of `filter` with the path `base`, so the patterns are relative to it.
+    /// An empty filter selects `base` itself.
+    pub(crate) fn join(base: &str, filter: &str) -> String {
+        let patterns: Vec<String> = split_outside_regex(filter, ",")
+            .into_iter()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("{}::{}", base, p))
+            .collect();
+        if patterns.is_empty() {
+            base.to_string()
+        } else {
+            patterns.join(", ")
+        }
+    }
+
+    /// The filter specification this filter was parsed from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Matches a `::`-separated item path against the filter. If any pattern is a hit, the result
+    /// is a hit. Otherwise descendants of a hit take precedence over ancestors of a possible hit.
+    pub fn matches(&self, path: &str) -> FilterMatch {
+        let components: Vec<&str> = path.split("::").collect();
+        let mut state = MatchState::default();
+        for pattern in &self.patterns {
+            Pattern::walk(&pattern.components, &components, &mut state);
+        }
+        if state.hit {
+            FilterMatch::Hit
+        } else if state.suffix {
+            FilterMatch::Suffix
+        } else if state.prefix {
+            FilterMatch::Prefix
+        } else {
+            FilterMatch::Miss
+        }
+    }
+}
+
+/// The kinds of item that can appear in a rendered module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Module,
+    Struct,
+    Enum,
+    Trait,
+    Function,
+    Constant,
+    TypeAlias,
+    Macro,
+    Import,
+}
+
+impl ItemKind {
+    /// All kinds, in the order they are listed in help text.
+    pub const ALL: &'static [ItemKind] = &[
+        ItemKind::Module,
+        ItemKind::Struct,
+        ItemKind::Enum,
+        ItemKind::Trait,
+        ItemKind::Function,
+        ItemKind::Constant,
+        ItemKind::TypeAlias,
+        ItemKind::Macro,
+        ItemKind::Import,
+    ];
+
+    /// Returns the kind of a module-level item, or None for items that are never rendered at
+    /// module level.
+    pub fn of(item: &Item) -> Option<Self> {
+        match &item.inner {
+            ItemEnum::Module(_) => Some(ItemKind::Module),
+            ItemEnum::Struct(_) => Some(ItemKind::Struct),
+            ItemEnum::Enum(_) => Some(ItemKind::Enum),
+            ItemEnum::Trait(_) => Some(ItemKind::Trait),
+            ItemEnum::Function(_) => Some(ItemKind::Function),
+            ItemEnum::Constant { .. } => Some(ItemKind::Constant),
+            ItemEnum::TypeAlias(_) => Some(ItemKind::TypeAlias),
+            ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => Some(ItemKind::Macro),
+            ItemEnum::Import(_) => Some(ItemKind::Import),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Module => "mod",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::Function => "fn",
+            ItemKind::Constant => "const",
+            ItemKind::TypeAlias => "type",
+            ItemKind::Macro => "macro",
+            ItemKind::Import => "use",
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ItemKind {
+    type Err = RuskelError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "mod" | "module" => Ok(ItemKind::Module),
+            "struct" => Ok(ItemKind::Struct),
+            "enum" => Ok(ItemKind::Enum),
+            "trait" => Ok(ItemKind::Trait),
+            "fn" | "function" => Ok(ItemKind::Function),
+            "const" | "constant" => Ok(ItemKind::Constant),
+            "type" => Ok(ItemKind::TypeAlias),
+            "macro" => Ok(ItemKind::Macro),
+            "use" | "import" => Ok(ItemKind::Import),
+            _ => Err(RuskelError::InvalidFilter(format!(
+                "unknown item kind '{}', expected one of: {}",
+                s,
+                ItemKind::ALL
+                    .iter()
+                    .map(|k| k.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+}
+
+/// A pattern selecting items to drop from the output.
+///
+/// An exclusion is a [`Filter`] pattern, optionally prefixed with an item kind and a colon,
+/// e.g. `macro:*`. A prefix that couldn't be a kind name, such as one containing a slash or
+/// glob characters, is part of the pattern, so regex components like `/a:b/` can contain colons.
+/// A pattern without any `::` separators matches item names at any depth, so `__private`
+/// excludes every module or item with that name.
+#[derive(Debug)]
+pub struct Exclude {
+    kind: Option<ItemKind>,
+    filter: Filter,
+}
+
+impl Exclude {
+    pub fn parse(exclude: &str) -> Result<Self> {
+        let exclude = exclude.trim();
+        let (kind, pattern) = match exclude.split_once(':') {
+            Some((kind, rest))
+                if !rest.starts_with(':')
+                    && !kind.is_empty()
+                    && kind.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                (Some(kind.parse()?), rest)
+            }
+            _ => (None, exclude),
+        };
+        let filter = if pattern.contains("::") {
+            Filter::parse(pattern)?
+        } else {
+            Filter::parse(&format!("**::{}", pattern))?
+        };
+        Ok(Exclude { kind, filter })
+    }
+
+    /// Does this exclusion drop the item at the given path?
+    pub fn matches(&self, path: &str, item: &Item) -> bool {
+        if let Some(kind) = self.kind {
+            if ItemKind::of(item) != Some(kind) {
+                return false;
+            }
+        }
+        matches!(
+            self.filter.matches(path),
+            FilterMatch::Hit | FilterMatch::Suffix
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(filter: &str, cases: &[(&str, FilterMatch)]) {
+        let f = Filter::parse(filter).unwrap();
+        for (path, expected) in cases {
+            assert_eq!(&f.matches(path), expected, "{} against {}", filter, path);
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        check(
+            "a::b",
+            &[
+                ("a", FilterMatch::Prefix),
+                ("a::b", FilterMatch::Hit),
+                ("a::b::c", FilterMatch::Suffix),
+                ("a::c", FilterMatch::Miss),
+                ("b", FilterMatch::Miss),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_glob() {
+        check(
+            "serde::de::*Deserialize*",
+            &[
+                ("serde::de", FilterMatch::Prefix),
+                ("serde::de::Deserialize", FilterMatch::Hit),
+                ("serde::de::DeserializeOwned", FilterMatch::Hit),
+                ("serde::de::Visitor", FilterMatch::Miss),
+                ("serde::ser::Deserialize", FilterMatch::Miss),
+            ],
+        );
+        check(
+            "a::b?",
+            &[("a::bc", FilterMatch::Hit), ("a::b", FilterMatch::Miss)],
+        );
+    }
+
+    #[test]
+    fn test_any_depth() {
+        check(
+            "tokio::**::spawn",
+            &[
+                ("tokio", FilterMatch::Prefix),
+                ("tokio::spawn", FilterMatch::Hit),
+                ("tokio::task::spawn", FilterMatch::Hit),
+                ("tokio::task::blocking", FilterMatch::Prefix),
+                ("tokio::task::spawn::inner", FilterMatch::Suffix),
+                ("other::spawn", FilterMatch::Miss),
+            ],
+        );
+        check(
+            "a::**",
+            &[("a", FilterMatch::Hit), ("a::b::c", FilterMatch::Hit)],
+        );
+    }
+
+    #[test]
+    fn test_regex() {
+        check(
+            "serde::/(de|ser)/::Error",
+            &[
+                ("serde::de::Error", FilterMatch::Hit),
+                ("serde::ser::Error", FilterMatch::Hit),
+                ("serde::des::Error", FilterMatch::Miss),
+            ],
+        );
+        check("a::/b{1,2}/", &[("a::bb", FilterMatch::Hit)]);
+        check("a::/x::y/", &[("a::x", FilterMatch::Miss)]);
+        assert!(Filter::parse("a::/(/").is_err());
+    }
+
+    #[test]
+    fn test_multiple() {
+        check(
+            "a::b, a::c::d",
+            &[
+                ("a", FilterMatch::Prefix),
+                ("a::b", FilterMatch::Hit),
+                ("a::c", FilterMatch::Prefix),
+                ("a::c::d", FilterMatch::Hit),
+                ("a::e", FilterMatch::Miss),
+            ],
+        );
+        assert!(Filter::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(Filter::join("serde", ""), "serde");
+        assert_eq!(
+            Filter::join("serde", "de::Deserialize"),
+            "serde::de::Deserialize"
+        );
+        assert_eq!(
+            Filter::join("serde::de", "Visitor, /a,b/::*"),
+            "serde::de::Visitor, serde::de::/a,b/::*"
+        );
+    }
+
+    #[test]
+    fn test_item_kind() {
+        for kind in ItemKind::ALL {
+            assert_eq!(&kind.name().parse::<ItemKind>().unwrap(), kind);
+        }
+        assert_eq!("function".parse::<ItemKind>().unwrap(), ItemKind::Function);
+        assert!("widget".parse::<ItemKind>().is_err());
+    }
+
+    #[test]
+    fn test_exclude_parse() {
+        let e = Exclude::parse("__private").unwrap();
+        assert_eq!(e.kind, None);
+        assert_eq!(e.filter.matches("a::b::__private"), FilterMatch::Hit);
+        assert_eq!(e.filter.matches("a::__private::x"), FilterMatch::Suffix);
+        assert_eq!(e.filter.matches("a::private"), FilterMatch::Prefix);
+
+        let e = Exclude::parse("a::tests").unwrap();
+        assert_eq!(e.filter.matches("b::tests"), FilterMatch::Miss);
+
+        let e = Exclude::parse("macro:*").unwrap();
+        assert_eq!(e.kind, Some(ItemKind::Macro));
+        assert_eq!(e.filter.matches("a::my_macro"), FilterMatch::Hit);
+
+        let e = Exclude::parse("/a:b/").unwrap();
+        assert_eq!(e.kind, None);
+        assert_eq!(e.filter.matches("x::a:b"), FilterMatch::Hit);
+
+        assert!(Exclude::parse("widget:*").is_err());
+        assert!(Exclude::parse("fucntion:*").is_err());
+    }
+}
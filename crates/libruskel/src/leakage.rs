@@ -0,0 +1,208 @@
+//! Finds the items of other crates that appear in a crate's public API.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, StructKind, Visibility};
+
+use crate::signature::signature_paths;
+
+/// An item of another crate that appears in the public API of the crate being examined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exposure {
+    /// The name of the crate the item is defined in.
+    pub crate_name: String,
+    /// The path of the item where it's defined, e.g. `alpha::config::Config`.
+    pub item: String,
+    /// The public items whose signatures use it, e.g. `beta::connect`, sorted.
+    pub used_by: Vec<String>,
+}
+
+/// Returns the items of other crates used in the public signatures of the crate, sorted by crate
+/// and path. Public items reachable through re-exports are included, and re-exports of other
+/// crates' items count as uses.
+pub fn public_exposures(crate_data: &Crate) -> Vec<Exposure> {
+    let mut walker = Walker {
+        crate_data,
+        found: BTreeMap::new(),
+        visited: HashSet::new(),
+    };
+    if let Some(root) = crate_data.index.get(&crate_data.root) {
+        let name = root.name.clone().unwrap_or_default();
+        walker.walk_module(&name, root);
+    }
+    walker
+        .found
+        .into_iter()
+        .map(|((crate_name, item), used_by)| Exposure {
+            crate_name,
+            item,
+            used_by: used_by.into_iter().collect(),
+        })
+        .collect()
+}
+
+struct Walker<'a> {
+    crate_data: &'a Crate,
+    /// Maps (crate, item path) to the locations that use the item.
+    found: BTreeMap<(String, String), BTreeSet<String>>,
+    /// Local items already walked, so re-exports and module cycles are only visited once.
+    visited: HashSet<Id>,
+}
+
+impl Walker<'_> {
+    /// Records a use of the item with the given id if it comes from another crate.
+    fn record(&mut self, id: &Id, location: &str) {
+        let Some(summary) = self.crate_data.paths.get(id) else {
+            return;
+        };
+        if summary.crate_id == 0 {
+            return;
+        }
+        let Some(external) = self.crate_data.external_crates.get(&summary.crate_id) else {
+            return;
+        };
+        self.found
+            .entry((external.name.clone(), summary.path.join("::")))
+            .or_default()
+            .insert(location.to_string());
+    }
+
+    fn walk_module(&mut self, path: &str, module: &Item) {
+        let ItemEnum::Module(module) = &module.inner else {
+            return;
+        };
+        for id in &module.items {
+            let Some(item) = self.crate_data.index.get(id) else {
+                continue;
+            };
+            if !matches!(item.visibility, Visibility::Public) {
+                continue;
+            }
+            if let ItemEnum::Import(import) = &item.inner {
+                let Some(target) = &import.id else {
+                    continue;
+                };
+                let location = if import.glob {
+                    format!("{}::*", path)
+                } else {
+                    format!("{}::{}", path, import.name)
+                };
+                match self.crate_data.index.get(target) {
+                    // A local item that may only be public through this re-export
+                    Some(target_item) if target_item.crate_id == 0 => {
+                        let target_path = if import.glob { path } else { &location };
+                        self.walk_item(target_path, target, target_item);
+                    }
+                    _ => self.record(target, &format!("{} (re-export)", location)),
+                }
+                continue;
+            }
+            let item_path = match &item.name {
+                Some(name) => format!("{}::{}", path, name),
+                None => path.to_string(),
+            };
+            self.walk_item(&item_path, id, item);
+        }
+    }
+
+    fn record_signature(&mut self, item: &Item, location: &str) {
+        for id in signature_paths(self.crate_data, item) {
+            self.record(&id, location);
+        }
+    }
+
+    fn walk_item(&mut self, path: &str, id: &Id, item: &Item) {
+        if !self.visited.insert(id.clone()) {
+            return;
+        }
+        self.record_signature(item, path);
+        match &item.inner {
+            ItemEnum::Module(_) => self.walk_module(path, item),
+            ItemEnum::Struct(struct_) => {
+                match &struct_.kind {
+                    StructKind::Unit => {}
+                    StructKind::Tuple(fields) => self.walk_fields(path, fields.iter().flatten()),
+                    StructKind::Plain { fields, .. } => self.walk_fields(path, fields.iter()),
+                }
+                self.walk_impls(path, &struct_.impls);
+            }
+            ItemEnum::Union(union_) => {
+                self.walk_fields(path, union_.fields.iter());
+                self.walk_impls(path, &union_.impls);
+            }
+            ItemEnum::Enum(enum_) => {
+                // Variant signatures cover their fields, which are all public
+                for variant in enum_
+                    .variants
+                    .iter()
+                    .filter_map(|id| self.crate_data.index.get(id))
+                {
+                    let variant_path =
+                        format!("{}::{}", path, variant.name.as_deref().unwrap_or("?"));
+                    self.record_signature(variant, &variant_path);
+                }
+                self.walk_impls(path, &enum_.impls);
+            }
+            ItemEnum::Trait(trait_) => {
+                for item_id in &trait_.items {
+                    if let Some(trait_item) = self.crate_data.index.get(item_id) {
+                        let item_path =
+                            format!("{}::{}", path, trait_item.name.as_deref().unwrap_or("?"));
+                        self.walk_item(&item_path, item_id, trait_item);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Walks the types of the public fields of a struct or union.
+    fn walk_fields<'b>(&mut self, path: &str, fields: impl Iterator<Item = &'b Id>) {
+        for (i, field_id) in fields.enumerate() {
+            let Some(field) = self.crate_data.index.get(field_id) else {
+                continue;
+            };
+            if !matches!(field.visibility, Visibility::Public) {
+                continue;
+            }
+            let name = field.name.clone().unwrap_or_else(|| i.to_string());
+            self.record_signature(field, &format!("{}::{}", path, name));
+        }
+    }
+
+    fn walk_impls(&mut self, path: &str, impls: &[Id]) {
+        for impl_id in impls {
+            let Some(item) = self.crate_data.index.get(impl_id) else {
+                continue;
+            };
+            let ItemEnum::Impl(impl_) = &item.inner else {
+                continue;
+            };
+            if impl_.synthetic || impl_.blanket_impl.is_some() {
+                continue;
+            }
+            match &impl_.trait_ {
+                Some(trait_) => {
+                    let trait_name = self
+                        .crate_data
+                        .paths
+                        .get(&trait_.id)
+                        .map_or_else(|| trait_.name.clone(), |summary| summary.path.join("::"));
+                    self.record_signature(item, &format!("impl {} for {}", trait_name, path));
+                }
+                None => self.record_signature(item, path),
+            }
+            for item_id in &impl_.items {
+                let Some(impl_item) = self.crate_data.index.get(item_id) else {
+                    continue;
+                };
+                // Items of trait impls are as public as the trait
+                if impl_.trait_.is_none() && !matches!(impl_item.visibility, Visibility::Public) {
+                    continue;
+                }
+                let item_path = format!("{}::{}", path, impl_item.name.as_deref().unwrap_or("?"));
+                self.walk_item(&item_path, item_id, impl_item);
+            }
+        }
+    }
+}
@@ -12,6 +12,8 @@
 //!
 //! You must have the nightly Rust toolchain installed to use (but not to install) RUskel.
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use syntect::{
     easy::HighlightLines,
     highlighting::ThemeSet,
@@ -19,16 +21,41 @@ use syntect::{
     util::{as_24_bit_terminal_escaped, LinesWithEndings},
 };
 
-use rustdoc_types::Crate;
+use rustdoc_types::{Crate, Id, ItemEnum};
 
 mod cargoutils;
+mod coverage;
 mod crateutils;
 mod error;
+mod explain;
+mod filter;
+mod leakage;
 mod render;
+mod rustdoc;
+mod search;
+mod signature;
+mod snapshot;
+mod source;
+mod stats;
+mod surface;
+mod usage;
 
+pub use crate::coverage::{DocCoverage, ModuleCoverage};
 pub use crate::error::{Result, RuskelError};
-pub use crate::render::Renderer;
+pub use crate::filter::{Exclude, Filter, FilterMatch, ItemKind};
+pub use crate::render::{Deprecated, Order, Renderer, SourceLocations};
+pub use crate::rustdoc::CrateTarget;
+pub use crate::search::SearchResult;
+pub use crate::signature::SignatureLink;
+pub use crate::snapshot::{
+    check_api_snapshot, check_snapshot, write_snapshot, UPDATE_SNAPSHOTS_VAR,
+};
+pub use crate::stats::{ApiStats, ModuleStats};
+pub use crate::usage::{FunctionUse, TypeUses, UsageIndex};
 use cargoutils::*;
+use leakage::{public_exposures, Exposure};
+use rustdoc::RustdocBuilder;
+use surface::PublicPaths;
 
 /// Ruskel generates a skeletonized version of a Rust crate in a single page.
 /// It produces syntactically valid Rust code with all implementations omitted.
@@ -56,6 +83,18 @@ pub struct Ruskel {
 
     /// In offline mode Ruskell will not attempt to fetch dependencies from the network.
     offline: bool,
+
+    /// The crate within the package to document.
+    crate_target: CrateTarget,
+
+    /// An alternative registry to fetch crates from.
+    registry: Option<String>,
+
+    /// A local source, such as a vendor directory, used in place of crates.io.
+    source_replacement: Option<SourceReplacement>,
+
+    /// Whether to include items marked `#[doc(hidden)]` in the generated crate data.
+    hidden_items: bool,
 }
 
 impl Ruskel {
@@ -67,6 +106,7 @@ impl Ruskel {
     /// - A module name (with or without path)
     /// - A package name (with or without path)
     /// - A fully qualified path to an item within a module
+    /// - A git repository, `git+<url>[#<rev>][::<package>[::<path>]]`
     /// - Blank, in which case we use the current directory
     ///
     /// The method normalizes package names, converting hyphens to underscores for internal use.
@@ -80,6 +120,7 @@ impl Ruskel {
     /// - serde::Deserialize
     /// - rustdoc-types::Crate
     /// - rustdoc_types::Crate
+    /// - git+https://host/repo.git#v1.0::mycrate::module
     ///
     /// The method will attempt to locate the appropriate Cargo.toml file and set up
     /// the filter for rendering based on the provided target.
@@ -93,6 +134,10 @@ impl Ruskel {
             features: Vec::new(),
             highlight: false,
             offline: false,
+            crate_target: CrateTarget::Lib,
+            registry: None,
+            source_replacement: None,
+            hidden_items: false,
         }
     }
 
@@ -103,6 +148,54 @@ impl Ruskel {
         self
     }
 
+    /// Selects the library, a binary or an example of the package to document.
+    pub fn with_crate_target(mut self, crate_target: CrateTarget) -> Self {
+        self.crate_target = crate_target;
+        self
+    }
+
+    /// Fetches crates named in the target from an alternative registry, as configured in
+    /// `.cargo/config.toml`, instead of crates.io.
+    pub fn with_registry(mut self, registry: &str) -> Self {
+        self.registry = Some(registry.to_string());
+        self
+    }
+
+    /// Replaces crates.io with a directory created by `cargo vendor`.
+    pub fn with_vendor_dir(mut self, path: &Path) -> Self {
+        self.source_replacement = Some(SourceReplacement::Vendor(path.to_path_buf()));
+        self
+    }
+
+    /// Replaces crates.io with a local registry directory.
+    pub fn with_local_registry(mut self, path: &Path) -> Self {
+        self.source_replacement = Some(SourceReplacement::LocalRegistry(path.to_path_buf()));
+        self
+    }
+
+    fn feature_config(&self) -> FeatureConfig {
+        FeatureConfig {
+            no_default_features: self.no_default_features,
+            all_features: self.all_features,
+            features: self.features.clone(),
+        }
+    }
+
+    fn source_config(&self) -> SourceConfig {
+        SourceConfig {
+            offline: self.offline,
+            registry: self.registry.clone(),
+            replacement: self.source_replacement.clone(),
+        }
+    }
+
+    /// Includes items marked `#[doc(hidden)]` in the crate data, which rustdoc strips by default.
+    /// The renderer still hides them unless [`Renderer::with_hidden_items`] is also set.
+    pub fn with_hidden_items(mut self, hidden_items: bool) -> Self {
+        self.hidden_items = hidden_items;
+        self
+    }
+
     /// Enables or disables syntax highlighting in the output.
     pub fn with_highlighting(mut self, highlight: bool) -> Self {
         self.highlight = highlight;
@@ -150,39 +243,238 @@ impl Ruskel {
         Ok(output)
     }
 
-    fn crate_from_package(&self, package_path: CargoPath) -> Result<Crate> {
-        let json_path = rustdoc_json::Builder::default()
-            .toolchain("nightly")
-            .manifest_path(package_path.manifest_path())
+    fn crate_from_package(
+        &self,
+        package_path: &CargoPath,
+        dependent: Option<&CargoPath>,
+    ) -> Result<Crate> {
+        // Private items are always included, and the renderer decides what to show
+        let json_path = RustdocBuilder::new(&package_path.manifest_path())
+            .target(&self.crate_target)
+            .dependent(dependent.map(|d| d.manifest_path()).as_deref())
             .document_private_items(true)
+            .document_hidden_items(self.hidden_items)
             .no_default_features(self.no_default_features)
             .all_features(self.all_features)
             .features(&self.features)
-            .build()
-            .map_err(|e| RuskelError::Generate(e.to_string()))?;
+            .offline(self.offline)
+            .config(&self.source_config().cargo_config())
+            .build()?;
         let json_content = fs::read_to_string(&json_path)?;
         let crate_data: Crate = serde_json::from_str(&json_content)?;
         Ok(crate_data)
     }
 
+    /// Resolves the target and builds its crate data, which can then be rendered and searched any
+    /// number of times without running cargo again.
+    pub fn load(&self) -> Result<LoadedCrate> {
+        self.build(&self.resolve()?)
+    }
+
+    /// Resolves the target to the package it names, fetching it if needed, without building it.
+    pub fn resolve(&self) -> Result<ResolvedPackage> {
+        let target = resolve_target(
+            &self.target,
+            Path::new("."),
+            &self.source_config(),
+            &self.feature_config(),
+        )?;
+        Ok(ResolvedPackage { target })
+    }
+
+    /// Builds the crate data of a package resolved by [`Ruskel::resolve`].
+    pub fn build(&self, resolved: &ResolvedPackage) -> Result<LoadedCrate> {
+        let rt = &resolved.target;
+        // Spans are relative to the workspace cargo ran in. A generated dummy package is its own
+        // workspace, and is deleted once built.
+        let build_root = rt.dependent.as_ref().unwrap_or(&rt.package_path);
+        let source_root = OnceLock::new();
+        if let CargoPath::TempDir(dir) = build_root {
+            let _ = source_root.set(dir.path().to_path_buf());
+        }
+        let build_dir = build_root.as_path().to_path_buf();
+        let package_root = rt.package_path.as_path().to_path_buf();
+        let package_dir = match (&rt.package_path, &rt.dependency) {
+            (CargoPath::Path(path), None) => Some(path.clone()),
+            _ => None,
+        };
+        let crate_data = self.crate_from_package(&rt.package_path, rt.dependent.as_ref())?;
+        Ok(LoadedCrate {
+            crate_data: Arc::new(crate_data),
+            filter: rt.filter.clone(),
+            build_dir,
+            source_root,
+            package_root,
+            package_dir,
+            dependency: rt.dependency.clone(),
+        })
+    }
+
     /// Generates and returns the parsed JSON representation of the crate's API.
     pub fn make_crate(&self) -> Result<Crate> {
-        let rt = resolve_target(&self.target, self.offline)?;
-        self.crate_from_package(rt.package_path)
+        let rt = resolve_target(
+            &self.target,
+            Path::new("."),
+            &self.source_config(),
+            &self.feature_config(),
+        )?;
+        self.crate_from_package(&rt.package_path, rt.dependent.as_ref())
     }
 
     /// Generates a skeletonized version of the crate as a string of Rust code.
     pub fn render(&self, auto_impls: bool, private_items: bool) -> Result<String> {
-        let rt = resolve_target(&self.target, self.offline)?;
-        let crate_data = self.crate_from_package(rt.package_path)?;
+        self.render_with(
+            Renderer::default()
+                .with_auto_impls(auto_impls)
+                .with_private_items(private_items),
+        )
+    }
+
+    /// Generates a skeletonized version of the crate using a custom renderer. If the target
+    /// specifies a path within the crate, any filter set on the renderer is relative to it. Output
+    /// for dependencies and registry crates starts with a comment naming the resolved version.
+    pub fn render_with(&self, renderer: Renderer) -> Result<String> {
+        let rendered = self.load()?.render(renderer)?;
+        if self.highlight {
+            self.highlight_code(&rendered)
+        } else {
+            Ok(rendered)
+        }
+    }
+
+    /// Selects the libraries of the workspace containing the target directory whose package names
+    /// match one of the patterns, or all of them if `packages` is empty, and builds them with a
+    /// single cargo invocation. Returns the workspace root and each library with its crate data.
+    fn workspace_crates(
+        &self,
+        packages: &[String],
+    ) -> Result<(CargoPath, Vec<(WorkspaceLib, Crate)>)> {
+        let root = CargoPath::nearest_manifest(&PathBuf::from(&self.target))
+            .ok_or(RuskelError::ManifestNotFound)?;
+        let workspace_root = CargoPath::Path(root.workspace_root()?);
 
-        let renderer = Renderer::default()
-            .with_filter(&rt.filter)
-            .with_auto_impls(auto_impls)
-            .with_private_items(private_items);
+        let selection = Filter::parse(&packages.join(","))?;
+        let members: Vec<WorkspaceLib> = workspace_root
+            .workspace_libs()?
+            .into_iter()
+            .filter(|lib| selection.is_empty() || selection.matches(&lib.name) == FilterMatch::Hit)
+            .collect();
+        if members.is_empty() {
+            return Err(RuskelError::ModuleNotFound(format!(
+                "no workspace members with a library match {}",
+                if packages.is_empty() {
+                    "the workspace".to_string()
+                } else {
+                    packages.join(", ")
+                }
+            )));
+        }
+
+        let names: Vec<String> = members.iter().map(|lib| lib.name.clone()).collect();
+        let json_paths = RustdocBuilder::new(&workspace_root.manifest_path())
+            .document_private_items(true)
+            .document_hidden_items(self.hidden_items)
+            .no_default_features(self.no_default_features)
+            .all_features(self.all_features)
+            .features(&self.features)
+            .offline(self.offline)
+            .config(&self.source_config().cargo_config())
+            .build_workspace(&names)?;
+
+        let mut crates = Vec::new();
+        for (lib, json_path) in members.into_iter().zip(json_paths) {
+            let crate_data: Crate = serde_json::from_str(&fs::read_to_string(&json_path)?)?;
+            crates.push((lib, crate_data));
+        }
+        Ok((workspace_root, crates))
+    }
+
+    /// Renders every library in the workspace containing the target directory into one document,
+    /// with a top-level module per crate. If `packages` is non-empty, only members whose names
+    /// match one of its patterns are rendered (see [`Filter`] for the syntax). All members are
+    /// built with a single cargo invocation. Crates that the renderer's filter doesn't match are
+    /// left out, and source locations are shown relative to the workspace root.
+    pub fn render_workspace(&self, renderer: Renderer, packages: &[String]) -> Result<String> {
+        let (workspace_root, crates) = self.workspace_crates(packages)?;
+
+        let renderer = renderer
+            .with_source_root(workspace_root.as_path())
+            .with_package_root(workspace_root.as_path());
+        let mut rendered = Vec::new();
+        for (_, crate_data) in &crates {
+            match renderer.render(crate_data) {
+                Ok(output) => rendered.push(output),
+                Err(RuskelError::FilterNotMatched(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if rendered.is_empty() {
+            return Err(RuskelError::FilterNotMatched(renderer.filter().to_string()));
+        }
+        let rendered = rendered.join("\n");
+
+        if self.highlight {
+            self.highlight_code(&rendered)
+        } else {
+            Ok(rendered)
+        }
+    }
+
+    /// Reports, for each workspace library and each workspace library it depends on, which items
+    /// of the dependency appear in its public API. Upgrading a dependency with exposed items can
+    /// be a breaking change for the dependent. `packages` selects the dependents to examine, as
+    /// for [`Ruskel::render_workspace`].
+    pub fn dependency_map(&self, packages: &[String]) -> Result<String> {
+        let (_, crates) = self.workspace_crates(packages)?;
+
+        let mut output = String::new();
+        for (lib, crate_data) in &crates {
+            let exposures = public_exposures(crate_data);
+            for dependency in &lib.dependencies {
+                let exposed: Vec<&Exposure> = exposures
+                    .iter()
+                    .filter(|e| &e.crate_name == dependency)
+                    .collect();
+                if exposed.is_empty() {
+                    output.push_str(&format!(
+                        "{} -> {}: not exposed\n",
+                        lib.crate_name, dependency
+                    ));
+                    continue;
+                }
+                output.push_str(&format!("{} -> {}\n", lib.crate_name, dependency));
+                for exposure in exposed {
+                    output.push_str(&format!("    {}\n", exposure.item));
+                    for used_by in &exposure.used_by {
+                        output.push_str(&format!("        {}\n", used_by));
+                    }
+                }
+            }
+        }
+        if output.is_empty() {
+            output.push_str("no dependencies between workspace libraries\n");
+        }
+        Ok(output)
+    }
 
-        let rendered = renderer.render(&crate_data)?;
+    /// Renders the type or trait named by the target along with everything in the crate related
+    /// to it: its inherent and trait impls, the functions that take or return it, and the traits
+    /// it appears in the bounds of. Output for dependencies and registry crates starts with a
+    /// comment naming the resolved version.
+    pub fn explain(&self, renderer: Renderer) -> Result<String> {
+        let rendered = self.load()?.explain(renderer)?;
+        if self.highlight {
+            self.highlight_code(&rendered)
+        } else {
+            Ok(rendered)
+        }
+    }
 
+    /// Renders the crate organized by public path: each public item appears once, in the module of
+    /// the shortest path users can name it by, with comments noting the other paths it's
+    /// re-exported at. If the target names a module, only that module is rendered.
+    pub fn render_public_paths(&self, renderer: Renderer) -> Result<String> {
+        let rendered = self.load()?.render_public_paths(renderer)?;
         if self.highlight {
             self.highlight_code(&rendered)
         } else {
@@ -190,8 +482,239 @@ impl Ruskel {
         }
     }
 
+    /// Renders the crate for an API snapshot: in source order, without highlighting or source
+    /// locations, and normalized so that output only changes when the API does.
+    pub fn snapshot(&self, renderer: Renderer) -> Result<String> {
+        let rendered = self.load()?.render(
+            renderer
+                .with_order(Order::Source)
+                .with_source_locations(SourceLocations::Off),
+        )?;
+        Ok(snapshot::normalize(&rendered))
+    }
+
+    /// Lists the functions and methods that return or take the type named by the target, for
+    /// finding out how to get hold of a value of the type and what it can be passed to. Private
+    /// functions are only included if `private_items` is set.
+    pub fn uses(&self, private_items: bool) -> Result<TypeUses> {
+        self.load()?.uses(private_items)
+    }
+
+    /// Collects metrics for each public module of the crate: item counts by kind, undocumented
+    /// items, generic parameters and bounds, unsafe functions and deprecated items. If the target
+    /// names a module, only it and the modules below it are included.
+    pub fn stats(&self, private_items: bool) -> Result<ApiStats> {
+        self.load()?.stats(private_items)
+    }
+
+    /// Checks the documentation of the crate's public surface, including struct fields, enum
+    /// variants, methods and trait items, reporting coverage per module. If the target names a
+    /// module, only it and the modules below it are checked.
+    pub fn doc_coverage(&self, private_items: bool) -> Result<DocCoverage> {
+        self.load()?.doc_coverage(private_items)
+    }
+
+    /// Finds items in the crate whose names contain the query, ignoring case. Queries containing
+    /// `::` are matched against full paths instead. Private items are only included if
+    /// `private_items` is set.
+    pub fn search(&self, query: &str, private_items: bool) -> Result<Vec<SearchResult>> {
+        Ok(self.load()?.search(query, private_items))
+    }
+
     /// Returns a pretty-printed version of the crate's JSON representation.
     pub fn raw_json(&self) -> Result<String> {
         Ok(serde_json::to_string_pretty(&self.make_crate()?)?)
     }
 }
+
+/// A target resolved to the package it names, by [`Ruskel::resolve`].
+#[derive(Debug)]
+pub struct ResolvedPackage {
+    target: ResolvedTarget,
+}
+
+impl ResolvedPackage {
+    /// The directory of the package's source. Targets naming the same package resolve to the
+    /// same directory, and other versions or sources of it to other directories.
+    pub fn package_root(&self) -> &Path {
+        self.target.package_path.as_path()
+    }
+}
+
+/// A crate built by [`Ruskel::load`], along with what's needed to render it as the target
+/// specified. Clones share the crate data.
+#[derive(Debug, Clone)]
+pub struct LoadedCrate {
+    crate_data: Arc<Crate>,
+    /// The path within the crate named by the target.
+    filter: String,
+    /// The directory of the package cargo ran in.
+    build_dir: PathBuf,
+    /// The workspace root that spans are relative to, found when first needed.
+    source_root: OnceLock<PathBuf>,
+    package_root: PathBuf,
+    package_dir: Option<PathBuf>,
+    dependency: Option<(String, semver::Version)>,
+}
+
+impl LoadedCrate {
+    /// The crate's rustdoc JSON representation.
+    pub fn crate_data(&self) -> &Crate {
+        &self.crate_data
+    }
+
+    /// Returns the crate as named by another target that resolves to the same package, sharing
+    /// its data.
+    pub fn retarget(&self, resolved: &ResolvedPackage) -> LoadedCrate {
+        LoadedCrate {
+            filter: resolved.target.filter.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// The path within the crate named by the target, e.g. `serde::de`, if it named one.
+    pub fn target_path(&self) -> Option<&str> {
+        (!self.filter.is_empty()).then_some(self.filter.as_str())
+    }
+
+    /// Returns the types and traits the signature of the item with the given id refers to,
+    /// including through its fields, variants or trait items. Private members are only followed
+    /// if `private_items` is set.
+    pub fn signature_links(&self, id: &Id, private_items: bool) -> Vec<SignatureLink> {
+        signature::signature_links(&self.crate_data, id, private_items)
+    }
+
+    /// The directory of the package if it's local source that can change, as opposed to a
+    /// dependency or a crate fetched from a registry or git.
+    pub fn package_dir(&self) -> Option<&Path> {
+        self.package_dir.as_deref()
+    }
+
+    /// Points the renderer at the crate's source, if it reads source for locations or expanded
+    /// bodies. Finding the workspace root spans are relative to means loading the workspace, so
+    /// it's only done then.
+    fn with_sources(&self, renderer: Renderer) -> Result<Renderer> {
+        if !renderer.reads_source() {
+            return Ok(renderer);
+        }
+        let source_root = match self.source_root.get() {
+            Some(root) => root,
+            None => {
+                let root = CargoPath::Path(self.build_dir.clone()).workspace_root()?;
+                self.source_root.get_or_init(|| root)
+            }
+        };
+        Ok(renderer
+            .with_source_root(source_root)
+            .with_package_root(&self.package_root))
+    }
+
+    /// Renders the crate without highlighting. If the target specified a path within the crate,
+    /// any filter set on the renderer is relative to it. Output for dependencies and registry
+    /// crates starts with a comment naming the resolved version.
+    pub fn render(&self, renderer: Renderer) -> Result<String> {
+        let renderer = self.with_sources(renderer)?;
+        let renderer = if self.filter.is_empty() {
+            renderer
+        } else {
+            let filter = Filter::join(&self.filter, renderer.filter());
+            renderer.with_filter(&filter)
+        };
+
+        let mut rendered = renderer.render(&self.crate_data)?;
+        // Report the concrete version a dependency or version requirement resolved to
+        if let Some((name, version)) = &self.dependency {
+            rendered = format!("// {} {}\n\n{}", name, version, rendered);
+        }
+        Ok(rendered)
+    }
+
+    /// Renders the crate organized by public path, as for [`Ruskel::render_public_paths`].
+    pub fn render_public_paths(&self, renderer: Renderer) -> Result<String> {
+        let renderer = self.with_sources(renderer)?;
+        let paths = PublicPaths::new(&self.crate_data, &renderer);
+        let index = if self.filter.is_empty() {
+            0
+        } else {
+            paths
+                .find_module(&self.filter)
+                .ok_or_else(|| RuskelError::ModuleNotFound(self.filter.clone()))?
+        };
+        let mut rendered = renderer.render_public_paths(&self.crate_data, &paths, index)?;
+        if let Some((name, version)) = &self.dependency {
+            rendered = format!("// {} {}\n\n{}", name, version, rendered);
+        }
+        Ok(rendered)
+    }
+
+    /// Renders the type or trait named by the target with everything related to it, as for
+    /// [`Ruskel::explain`].
+    pub fn explain(&self, renderer: Renderer) -> Result<String> {
+        if self.filter.is_empty() {
+            return Err(RuskelError::InvalidTarget(
+                "target must name a type or trait, e.g. serde_json::Value".to_string(),
+            ));
+        }
+        let renderer = self.with_sources(renderer)?;
+        let mut rendered = explain::explain(&self.crate_data, &renderer, &self.filter)?;
+        if let Some((name, version)) = &self.dependency {
+            rendered = format!("// {} {}\n\n{}", name, version, rendered);
+        }
+        Ok(rendered)
+    }
+
+    /// Lists the functions that return or take the type named by the target, as for
+    /// [`Ruskel::uses`].
+    pub fn uses(&self, private_items: bool) -> Result<TypeUses> {
+        let (id, item) = explain::find_item(&self.crate_data, &self.filter)
+            .filter(|_| !self.filter.is_empty())
+            .ok_or_else(|| {
+                RuskelError::InvalidTarget(format!(
+                    "'{}' does not name a type in the crate",
+                    self.filter
+                ))
+            })?;
+        if !matches!(
+            item.inner,
+            ItemEnum::Struct(_)
+                | ItemEnum::Enum(_)
+                | ItemEnum::Union(_)
+                | ItemEnum::Trait(_)
+                | ItemEnum::TypeAlias(_)
+        ) {
+            return Err(RuskelError::InvalidTarget(format!(
+                "'{}' is not a type or trait",
+                self.filter
+            )));
+        }
+        let name = item.name.as_deref().unwrap_or(&self.filter);
+        Ok(UsageIndex::new(&self.crate_data, private_items).uses(id, name))
+    }
+
+    /// Collects metrics for the crate's public modules, as for [`Ruskel::stats`].
+    pub fn stats(&self, private_items: bool) -> Result<ApiStats> {
+        let stats = ApiStats::new(&self.crate_data, private_items);
+        if self.filter.is_empty() {
+            return Ok(stats);
+        }
+        stats
+            .select(&self.filter)
+            .ok_or_else(|| RuskelError::ModuleNotFound(self.filter.clone()))
+    }
+
+    /// Checks the documentation of the crate's public surface, as for [`Ruskel::doc_coverage`].
+    pub fn doc_coverage(&self, private_items: bool) -> Result<DocCoverage> {
+        let coverage = DocCoverage::new(&self.crate_data, private_items);
+        if self.filter.is_empty() {
+            return Ok(coverage);
+        }
+        coverage
+            .select(&self.filter)
+            .ok_or_else(|| RuskelError::ModuleNotFound(self.filter.clone()))
+    }
+
+    /// Finds items in the crate by name, as for [`Ruskel::search`].
+    pub fn search(&self, query: &str, private_items: bool) -> Vec<SearchResult> {
+        search::search(&self.crate_data, query, private_items)
+    }
+}
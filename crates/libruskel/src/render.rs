@@ -1,3 +1,6 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
 use rust_format::{Config, Formatter, RustFmt};
 use rustdoc_types::{
     Crate, Id, Impl, Item, ItemEnum, MacroKind, StructKind, VariantKind, Visibility,
@@ -5,17 +8,149 @@ use rustdoc_types::{
 
 use crate::crateutils::*;
 use crate::error::{Result, RuskelError};
+use crate::filter::{Exclude, Filter, FilterMatch, ItemKind};
+use crate::source::{function_body, span_text};
+use crate::surface::{PublicItem, PublicPaths};
 
 fn must_get<'a>(crate_data: &'a Crate, id: &Id) -> &'a Item {
     crate_data.index.get(id).unwrap()
 }
 
-#[derive(Debug, PartialEq)]
-enum FilterMatch {
-    Hit,
-    Prefix,
-    Suffix,
-    Miss,
+fn item_path(module_path: &str, item: &Item) -> String {
+    if let Some(name) = &item.name {
+        if module_path.is_empty() {
+            name.clone()
+        } else {
+            format!("{}::{}", module_path, name)
+        }
+    } else {
+        module_path.to_string()
+    }
+}
+
+/// The file an item is defined in, if it has a span.
+fn span_file(item: &Item) -> Option<&Path> {
+    item.span.as_ref().map(|span| span.filename.as_path())
+}
+
+/// The key items are sorted by in source order: items without spans last, then items defined
+/// outside `home`, then by file and position.
+fn source_key<'a>(item: &'a Item, home: Option<&Path>) -> (bool, bool, &'a Path, (usize, usize)) {
+    match &item.span {
+        Some(span) => (
+            false,
+            home.is_some_and(|home| home != span.filename),
+            &span.filename,
+            span.begin,
+        ),
+        None => (true, false, Path::new(""), (0, 0)),
+    }
+}
+
+/// Is the item deprecated? Imports are judged by the item they resolve to.
+fn is_deprecated(crate_data: &Crate, item: &Item) -> bool {
+    if item.deprecation.is_some() {
+        return true;
+    }
+    match &item.inner {
+        ItemEnum::Import(import) => import
+            .id
+            .as_ref()
+            .and_then(|id| crate_data.index.get(id))
+            .is_some_and(|target| target.deprecation.is_some()),
+        _ => false,
+    }
+}
+
+/// How deprecated items are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Deprecated {
+    /// Render deprecated items in place, marked with a `#[deprecated]` attribute.
+    #[default]
+    Inline,
+    /// Leave deprecated items out of the output entirely.
+    Hide,
+    /// Render deprecated items after all other items in each module.
+    Last,
+}
+
+impl FromStr for Deprecated {
+    type Err = RuskelError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "inline" => Ok(Deprecated::Inline),
+            "hide" => Ok(Deprecated::Hide),
+            "last" => Ok(Deprecated::Last),
+            _ => Err(RuskelError::InvalidOption(format!(
+                "unknown deprecated mode '{}', expected one of: inline, hide, last",
+                s
+            ))),
+        }
+    }
+}
+
+/// Whether and how source locations are rendered as comments above each item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceLocations {
+    /// Don't render source locations.
+    #[default]
+    Off,
+    /// Paths relative to the package root. Files outside the package, such as those of
+    /// dependencies, are shown as reported by rustdoc.
+    Relative,
+    /// Absolute paths.
+    Absolute,
+}
+
+impl FromStr for SourceLocations {
+    type Err = RuskelError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "off" => Ok(SourceLocations::Off),
+            "relative" => Ok(SourceLocations::Relative),
+            "absolute" => Ok(SourceLocations::Absolute),
+            _ => Err(RuskelError::InvalidOption(format!(
+                "unknown source location mode '{}', expected one of: off, relative, absolute",
+                s
+            ))),
+        }
+    }
+}
+
+/// The order items are rendered in within modules, impls and traits. Struct fields and enum
+/// variants always keep their declared order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    /// The order rustdoc lists items in, which can change between toolchains.
+    #[default]
+    Rustdoc,
+    /// The order items are defined in the source, based on their spans. Items defined in other
+    /// files, such as the contents of `mod foo;` declarations, follow those defined inline.
+    Source,
+    /// Grouped by kind as for [`Order::Kind`], and sorted by name within each kind.
+    Alphabetical,
+    /// Grouped by kind: modules, re-exports, types, traits, constants, functions and macros, with
+    /// inherent impls before trait impls. Items of the same kind are in source order.
+    Kind,
+}
+
+impl FromStr for Order {
+    type Err = RuskelError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rustdoc" => Ok(Order::Rustdoc),
+            "source" => Ok(Order::Source),
+            "alphabetical" => Ok(Order::Alphabetical),
+            "kind" => Ok(Order::Kind),
+            _ => Err(RuskelError::InvalidOption(format!(
+                "unknown order '{}', expected one of: rustdoc, source, alphabetical, kind",
+                s
+            ))),
+        }
+    }
 }
 
 pub struct Renderer {
@@ -23,13 +158,27 @@ pub struct Renderer {
     render_auto_impls: bool,
     render_private_items: bool,
     render_blanket_impls: bool,
+    render_hidden_items: bool,
+    deprecated: Deprecated,
+    order: Order,
+    source_locations: SourceLocations,
+    source_root: Option<PathBuf>,
+    package_root: Option<PathBuf>,
     filter: String,
+    expand: String,
+    excludes: Vec<String>,
+    kinds: Vec<ItemKind>,
 }
 
 struct RenderState<'a, 'b> {
     config: &'a Renderer,
     crate_data: &'b Crate,
+    filter: Filter,
     filter_matched: bool,
+    expand: Filter,
+    excludes: Vec<Exclude>,
+    /// Whether structs are followed by their impl blocks.
+    struct_impls: bool,
 }
 
 impl Default for Renderer {
@@ -46,15 +195,52 @@ impl Renderer {
             render_auto_impls: false,
             render_private_items: false,
             render_blanket_impls: false,
+            render_hidden_items: false,
+            deprecated: Deprecated::Inline,
+            order: Order::Rustdoc,
+            source_locations: SourceLocations::Off,
+            source_root: None,
+            package_root: None,
             filter: String::new(),
+            expand: String::new(),
+            excludes: Vec::new(),
+            kinds: Vec::new(),
         }
     }
 
+    /// Restricts rendering to items matching the filter. See [`Filter`] for the pattern syntax.
     pub fn with_filter(mut self, filter: &str) -> Self {
         self.filter = filter.to_string();
         self
     }
 
+    /// Renders the original bodies of functions and methods matching the pattern, read back from
+    /// source using their spans, instead of `{}`. Matching a type or module expands every function
+    /// below it. See [`Filter`] for the pattern syntax.
+    pub fn with_expand(mut self, expand: &str) -> Self {
+        self.expand = expand.to_string();
+        self
+    }
+
+    /// Returns the filter pattern set with [`Renderer::with_filter`].
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Drops items matching the pattern from the output. May be called repeatedly. See
+    /// [`Exclude`] for the pattern syntax.
+    pub fn with_exclude(mut self, exclude: &str) -> Self {
+        self.excludes.push(exclude.to_string());
+        self
+    }
+
+    /// Restricts output to items of the given kinds, plus the modules that enclose them. An empty
+    /// list renders all kinds.
+    pub fn with_kinds(mut self, kinds: &[ItemKind]) -> Self {
+        self.kinds = kinds.to_vec();
+        self
+    }
+
     pub fn with_blanket_impls(mut self, render_blanket_impls: bool) -> Self {
         self.render_blanket_impls = render_blanket_impls;
         self
@@ -70,13 +256,123 @@ impl Renderer {
         self
     }
 
+    /// Renders items marked `#[doc(hidden)]`, and re-exports of them, which are hidden by default.
+    pub fn with_hidden_items(mut self, render_hidden_items: bool) -> Self {
+        self.render_hidden_items = render_hidden_items;
+        self
+    }
+
+    /// Sets how deprecated items are rendered.
+    pub fn with_deprecated(mut self, deprecated: Deprecated) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    /// Sets the order items are rendered in.
+    pub fn with_order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Renders a `// file:line` comment above each item.
+    pub fn with_source_locations(mut self, source_locations: SourceLocations) -> Self {
+        self.source_locations = source_locations;
+        self
+    }
+
+    /// Sets the directory rustdoc was run from, which relative paths in item spans are resolved
+    /// against. For cargo builds this is the workspace root.
+    pub fn with_source_root(mut self, root: &Path) -> Self {
+        self.source_root = Some(root.to_path_buf());
+        self
+    }
+
+    /// Sets the package root that relative source locations are shown against.
+    pub fn with_package_root(mut self, root: &Path) -> Self {
+        self.package_root = Some(root.to_path_buf());
+        self
+    }
+
+    /// Does rendering read source files, for source locations or expanded bodies?
+    pub(crate) fn reads_source(&self) -> bool {
+        self.source_locations != SourceLocations::Off || !self.expand.is_empty()
+    }
+
+    /// Resolves the file an item was defined in to an absolute path, if possible.
+    fn source_path(&self, item: &Item) -> Option<PathBuf> {
+        let span = item.span.as_ref()?;
+        Some(match &self.source_root {
+            Some(root) if span.filename.is_relative() => root.join(&span.filename),
+            _ => span.filename.clone(),
+        })
+    }
+
     pub fn render(&self, crate_data: &Crate) -> Result<String> {
-        let mut state = RenderState {
+        self.state(crate_data, &self.filter)?.render()
+    }
+
+    /// Renders a single item outside of its module, ignoring the filter. `parent_path` is the path
+    /// of the module or type containing the item. Structs are rendered without their impls.
+    pub(crate) fn render_fragment(
+        &self,
+        crate_data: &Crate,
+        parent_path: &str,
+        item: &Item,
+    ) -> Result<String> {
+        let mut state = self.state(crate_data, "")?;
+        state.struct_impls = false;
+        let output = match &item.inner {
+            ItemEnum::Impl(_) => state.render_impl(parent_path, item),
+            ItemEnum::Function(_) if state.is_hidden(item) => String::new(),
+            ItemEnum::Function(_) => state.render_function(parent_path, item, true),
+            _ => state.render_item(parent_path, item, true),
+        };
+        if output.trim().is_empty() {
+            return Ok(String::new());
+        }
+        Ok(self.formatter.format_str(&output)?)
+    }
+
+    /// Is the item's visibility rendered?
+    pub(crate) fn is_visible(&self, item: &Item) -> bool {
+        self.render_private_items || matches!(item.visibility, Visibility::Public)
+    }
+
+    /// Is the item left out of the output, either because it is `#[doc(hidden)]` or because
+    /// deprecated items are hidden?
+    pub(crate) fn is_hidden(&self, crate_data: &Crate, item: &Item) -> bool {
+        (!self.render_hidden_items && is_doc_hidden(item))
+            || (self.deprecated == Deprecated::Hide && is_deprecated(crate_data, item))
+    }
+
+    /// Renders the module at `index` of the public paths and the modules below it, with each item
+    /// in the module of its shortest public path and comments noting its other paths. The filter
+    /// is ignored.
+    pub(crate) fn render_public_paths(
+        &self,
+        crate_data: &Crate,
+        paths: &PublicPaths,
+        index: usize,
+    ) -> Result<String> {
+        let mut state = self.state(crate_data, "")?;
+        let output = state.render_public_module(paths, index, true);
+        Ok(self.formatter.format_str(&output)?)
+    }
+
+    fn state<'a, 'b>(&'a self, crate_data: &'b Crate, filter: &str) -> Result<RenderState<'a, 'b>> {
+        Ok(RenderState {
             config: self,
+            filter: Filter::parse(filter)?,
             filter_matched: false,
+            expand: Filter::parse(&self.expand)?,
+            excludes: self
+                .excludes
+                .iter()
+                .map(|e| Exclude::parse(e))
+                .collect::<Result<Vec<_>>>()?,
+            struct_impls: true,
             crate_data,
-        };
-        state.render()
+        })
     }
 }
 
@@ -85,15 +381,129 @@ impl<'a, 'b> RenderState<'a, 'b> {
         // The root item is always a module
         let output = self.render_item("", must_get(self.crate_data, &self.crate_data.root), false);
 
-        if !self.config.filter.is_empty() && !self.filter_matched {
-            return Err(RuskelError::FilterNotMatched(self.config.filter.clone()));
+        if !self.filter.is_empty() && !self.filter_matched {
+            return Err(RuskelError::FilterNotMatched(
+                self.filter.source().to_string(),
+            ));
         }
 
         Ok(self.config.formatter.format_str(&output)?)
     }
 
+    fn render_source_location(&self, item: &Item) -> String {
+        let (Some(span), Some(path)) = (&item.span, self.config.source_path(item)) else {
+            return String::new();
+        };
+        let path = match self.config.source_locations {
+            SourceLocations::Off => return String::new(),
+            SourceLocations::Absolute => path,
+            SourceLocations::Relative => self
+                .config
+                .package_root
+                .as_ref()
+                .and_then(|root| path.strip_prefix(root).ok())
+                .map_or_else(|| span.filename.clone(), Path::to_path_buf),
+        };
+        format!("// {}:{}\n", path.display(), span.begin.0)
+    }
+
+    /// Renders the source location, doc comment and attributes that precede an item.
+    fn item_docs(&self, item: &Item) -> String {
+        let mut output = self.render_source_location(item);
+        output.push_str(&docs(item));
+        output
+    }
+
+    /// Returns the original body of a function selected for expansion, or `None` if the function
+    /// isn't selected or its source can't be read.
+    fn expanded_body(&self, parent_path: &str, item: &Item) -> Option<String> {
+        if self.expand.is_empty()
+            || !matches!(
+                self.expand.matches(&item_path(parent_path, item)),
+                FilterMatch::Hit | FilterMatch::Suffix
+            )
+        {
+            return None;
+        }
+        let source = std::fs::read_to_string(self.config.source_path(item)?).ok()?;
+        let text = span_text(&source, item.span.as_ref()?)?;
+        function_body(&text).map(|b| b.to_string())
+    }
+
     fn is_visible(&self, item: &Item) -> bool {
-        self.config.render_private_items || matches!(item.visibility, Visibility::Public)
+        self.config.is_visible(item)
+    }
+
+    fn is_hidden(&self, item: &Item) -> bool {
+        self.config.is_hidden(self.crate_data, item)
+    }
+
+    fn is_deprecated(&self, item: &Item) -> bool {
+        is_deprecated(self.crate_data, item)
+    }
+
+    /// Sorts items into the configured order. `home` is the file the enclosing item is defined
+    /// in, if any: its items come before those defined in other files.
+    fn sort_items(&self, items: &mut [&Item], home: Option<&Path>) {
+        self.sort_entries(items, home, |item| item);
+    }
+
+    /// Sorts entries into the configured order by the item each refers to, as for
+    /// [`Self::sort_items`].
+    fn sort_entries<T>(&self, entries: &mut [T], home: Option<&Path>, item: impl Fn(&T) -> &Item) {
+        let by_source = |a: &T, b: &T| source_key(item(a), home).cmp(&source_key(item(b), home));
+        let by_kind = |a: &T, b: &T| self.kind_rank(item(a)).cmp(&self.kind_rank(item(b)));
+        match self.config.order {
+            Order::Rustdoc => {}
+            Order::Source => entries.sort_by(by_source),
+            Order::Kind => entries.sort_by(|a, b| by_kind(a, b).then_with(|| by_source(a, b))),
+            Order::Alphabetical => entries.sort_by(|a, b| {
+                by_kind(a, b)
+                    .then_with(|| self.sort_name(item(a)).cmp(&self.sort_name(item(b))))
+                    .then_with(|| by_source(a, b))
+            }),
+        }
+    }
+
+    /// The position of an item's kind when items are grouped by kind. Imports are grouped with
+    /// the item they resolve to.
+    fn kind_rank(&self, item: &Item) -> u8 {
+        match &item.inner {
+            ItemEnum::Module(_) => 0,
+            ItemEnum::Import(import) => match import
+                .id
+                .as_ref()
+                .and_then(|id| self.crate_data.index.get(id))
+            {
+                Some(target) if !import.glob => self.kind_rank(target),
+                _ => 1,
+            },
+            ItemEnum::Struct(_)
+            | ItemEnum::Enum(_)
+            | ItemEnum::Union(_)
+            | ItemEnum::TypeAlias(_)
+            | ItemEnum::AssocType { .. } => 2,
+            ItemEnum::Trait(_) | ItemEnum::TraitAlias(_) => 3,
+            ItemEnum::Constant { .. } | ItemEnum::Static(_) | ItemEnum::AssocConst { .. } => 4,
+            ItemEnum::Function(_) => 5,
+            ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => 6,
+            ItemEnum::Impl(impl_) if impl_.trait_.is_none() => 7,
+            _ => 8,
+        }
+    }
+
+    /// The name items are sorted by alphabetically. Imports are sorted by the name they're
+    /// imported as, and impls by their trait and then their type.
+    fn sort_name(&self, item: &Item) -> String {
+        match &item.inner {
+            ItemEnum::Import(import) => import.name.clone(),
+            ItemEnum::Impl(impl_) => format!(
+                "{} {}",
+                impl_.trait_.as_ref().map(render_path).unwrap_or_default(),
+                render_type(&impl_.for_)
+            ),
+            _ => item.name.clone().unwrap_or_default(),
+        }
     }
 
     fn should_render_impl(&self, impl_: &Impl) -> bool {
@@ -154,7 +564,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
     }
 
     fn should_filter(&mut self, module_path: &str, item: &Item) -> bool {
-        if self.config.filter.is_empty() {
+        if self.filter.is_empty() {
             return false;
         }
         match self.filter_match(module_path, item) {
@@ -162,38 +572,41 @@ impl<'a, 'b> RenderState<'a, 'b> {
                 self.filter_matched = true;
                 false
             }
-            FilterMatch::Prefix | FilterMatch::Suffix => false,
+            // Only containers can hold a match further down the tree
+            FilterMatch::Prefix => !matches!(item.inner, ItemEnum::Module(_) | ItemEnum::Import(_)),
+            FilterMatch::Suffix => false,
             FilterMatch::Miss => true,
         }
     }
 
     fn filter_match(&self, module_path: &str, item: &Item) -> FilterMatch {
-        let item_path = if let Some(name) = &item.name {
-            if module_path.is_empty() {
-                name.clone()
-            } else {
-                format!("{}::{}", module_path, name)
-            }
-        } else {
-            module_path.to_string()
-        };
+        self.filter.matches(&item_path(module_path, item))
+    }
+
+    fn should_exclude(&self, module_path: &str, item: &Item) -> bool {
+        // The crate root is never excluded
+        if module_path.is_empty() || self.excludes.is_empty() {
+            return false;
+        }
+        let path = item_path(module_path, item);
+        self.excludes.iter().any(|e| e.matches(&path, item))
+    }
 
-        let filter_components: Vec<&str> = self.config.filter.split("::").collect();
-        let item_components: Vec<&str> = item_path.split("::").collect();
+    /// Is this kind of item selected for output? Modules are always traversed, and imports are
+    /// judged by the item they resolve to, so neither is checked here.
+    fn kind_selected(&self, kind: ItemKind) -> bool {
+        self.config.kinds.is_empty() || self.config.kinds.contains(&kind)
+    }
 
-        if item_path == self.config.filter {
-            FilterMatch::Hit
-        } else if filter_components.starts_with(&item_components) {
-            FilterMatch::Prefix
-        } else if item_components.starts_with(&filter_components) {
-            FilterMatch::Suffix
-        } else {
-            FilterMatch::Miss
+    fn should_skip_kind(&self, item: &Item) -> bool {
+        match ItemKind::of(item) {
+            Some(ItemKind::Module) | Some(ItemKind::Import) | None => false,
+            Some(kind) => !self.kind_selected(kind),
         }
     }
 
     fn should_module_doc(&self, module_path: &str, item: &Item) -> bool {
-        if self.config.filter.is_empty() {
+        if self.filter.is_empty() {
             return true;
         }
         matches!(
@@ -203,17 +616,21 @@ impl<'a, 'b> RenderState<'a, 'b> {
     }
 
     fn render_item(&mut self, module_path: &str, item: &Item, force_private: bool) -> String {
-        if self.should_filter(module_path, item) {
+        if self.is_hidden(item)
+            || self.should_filter(module_path, item)
+            || self.should_exclude(module_path, item)
+            || self.should_skip_kind(item)
+        {
             return String::new();
         }
 
         let output = match &item.inner {
             ItemEnum::Module(_) => self.render_module(module_path, item),
-            ItemEnum::Struct(_) => self.render_struct(item),
+            ItemEnum::Struct(_) => self.render_struct(module_path, item),
             ItemEnum::Enum(_) => self.render_enum(item),
-            ItemEnum::Trait(_) => self.render_trait(item),
+            ItemEnum::Trait(_) => self.render_trait(module_path, item),
             ItemEnum::Import(_) => self.render_import(module_path, item),
-            ItemEnum::Function(_) => self.render_function(item, false),
+            ItemEnum::Function(_) => self.render_function(module_path, item, false),
             ItemEnum::Constant { .. } => self.render_constant(item),
             ItemEnum::TypeAlias(_) => self.render_type_alias(item),
             ItemEnum::Macro(_) => self.render_macro(item),
@@ -228,8 +645,65 @@ impl<'a, 'b> RenderState<'a, 'b> {
         }
     }
 
+    /// Renders a module of the public paths view. As with other modules, it's dropped if it's
+    /// excluded, or if it's empty and modules aren't among the selected kinds.
+    fn render_public_module(&mut self, paths: &PublicPaths, index: usize, top: bool) -> String {
+        let module = &paths.modules[index];
+        let parent_path = module
+            .path
+            .rsplit_once("::")
+            .map_or("", |(parent, _)| parent);
+        if !top && self.should_exclude(parent_path, module.module) {
+            return String::new();
+        }
+        let mut output = String::new();
+        for alias in &module.aliases {
+            output.push_str(&format!("// Also available as {}\n", alias));
+        }
+        if !top {
+            output.push_str(&self.render_source_location(module.module));
+        }
+        output.push_str(&render_deprecation(module.module));
+        output.push_str(&format!("pub mod {} {{\n", module.name()));
+        if let Some(docs) = &module.module.docs {
+            for line in docs.lines() {
+                output.push_str(&format!("    //! {}\n", line));
+            }
+            output.push('\n');
+        }
+
+        let mut body = String::new();
+        for &child in &module.children {
+            body.push_str(&self.render_public_module(paths, child, false));
+        }
+        let mut entries: Vec<&PublicItem> = module.items.iter().collect();
+        self.sort_entries(&mut entries, span_file(module.module), |entry| entry.item);
+        for entry in entries {
+            let item = entry.item;
+            let rendered = self.render_item(&module.path, item, true);
+            if rendered.is_empty() {
+                continue;
+            }
+            if !matches!(item.inner, ItemEnum::Import(_)) && item.name.as_ref() != Some(&entry.name)
+            {
+                body.push_str(&format!("// Re-exported as {}\n", entry.name));
+            }
+            for alias in &entry.aliases {
+                body.push_str(&format!("// Also available as {}\n", alias));
+            }
+            body.push_str(&rendered);
+        }
+
+        if body.is_empty() && !top && !self.kind_selected(ItemKind::Module) {
+            return String::new();
+        }
+        output.push_str(&body);
+        output.push_str("}\n\n");
+        output
+    }
+
     fn render_proc_macro(&self, item: &Item) -> String {
-        let mut output = docs(item);
+        let mut output = self.item_docs(item);
 
         let fn_name = render_name(item);
 
@@ -270,7 +744,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
     }
 
     fn render_macro(&self, item: &Item) -> String {
-        let mut output = docs(item);
+        let mut output = self.item_docs(item);
 
         let macro_def = extract_item!(item, ItemEnum::Macro);
         // Add #[macro_export] for public macros
@@ -282,7 +756,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
 
     fn render_type_alias(&self, item: &Item) -> String {
         let type_alias = extract_item!(item, ItemEnum::TypeAlias);
-        let mut output = docs(item);
+        let mut output = self.item_docs(item);
 
         output.push_str(&format!(
             "{}type {}{}{}",
@@ -304,18 +778,24 @@ impl<'a, 'b> RenderState<'a, 'b> {
             if let Some(source_id) = &import.id {
                 if let Some(source_item) = self.crate_data.index.get(source_id) {
                     let module = extract_item!(source_item, ItemEnum::Module);
+                    let mut items: Vec<&Item> = module
+                        .items
+                        .iter()
+                        .filter_map(|id| self.crate_data.index.get(id))
+                        .filter(|item| self.is_visible(item))
+                        .collect();
+                    self.sort_items(&mut items, span_file(source_item));
                     let mut output = String::new();
-                    for item_id in &module.items {
-                        if let Some(item) = self.crate_data.index.get(item_id) {
-                            if self.is_visible(item) {
-                                output.push_str(&self.render_item(module_path, item, true));
-                            }
-                        }
+                    for item in items {
+                        output.push_str(&self.render_item(module_path, item, true));
                     }
                     return output;
                 }
             }
             // If we can't resolve the glob import, fall back to rendering it as-is
+            if !self.kind_selected(ItemKind::Import) {
+                return String::new();
+            }
             return format!("pub use {}::*;\n", import.source);
         }
 
@@ -327,7 +807,11 @@ impl<'a, 'b> RenderState<'a, 'b> {
             return self.render_item(module_path, imported_item, true);
         }
 
-        let mut output = docs(item);
+        if !self.kind_selected(ItemKind::Import) {
+            return String::new();
+        }
+
+        let mut output = self.item_docs(item);
         if import.name != import.source.split("::").last().unwrap_or(&import.source) {
             output.push_str(&format!("pub use {} as {};\n", import.source, import.name));
         } else {
@@ -337,11 +821,13 @@ impl<'a, 'b> RenderState<'a, 'b> {
         output
     }
 
-    fn render_impl(&self, item: &Item) -> String {
-        let mut output = docs(item);
+    /// Renders an impl block. `parent_path` is the path of the implementing type, under which
+    /// the impl's items are matched.
+    fn render_impl(&self, parent_path: &str, item: &Item) -> String {
+        let mut output = self.item_docs(item);
         let impl_ = extract_item!(item, ItemEnum::Impl);
 
-        if !self.should_render_impl(impl_) {
+        if !self.should_render_impl(impl_) || self.is_hidden(item) {
             return String::new();
         }
 
@@ -380,13 +866,17 @@ impl<'a, 'b> RenderState<'a, 'b> {
 
         output.push_str(" {\n");
 
-        for item_id in &impl_.items {
-            if let Some(item) = self.crate_data.index.get(item_id) {
-                let is_trait_impl = impl_.trait_.is_some();
-                if is_trait_impl || self.is_visible(item) {
-                    output.push_str(&self.render_impl_item(item));
-                }
-            }
+        let mut items: Vec<&Item> = impl_
+            .items
+            .iter()
+            .filter_map(|id| self.crate_data.index.get(id))
+            .filter(|item| {
+                (impl_.trait_.is_some() || self.is_visible(item)) && !self.is_hidden(item)
+            })
+            .collect();
+        self.sort_items(&mut items, span_file(item));
+        for item in items {
+            output.push_str(&self.render_impl_item(parent_path, item));
         }
 
         output.push_str("}\n\n");
@@ -394,9 +884,9 @@ impl<'a, 'b> RenderState<'a, 'b> {
         output
     }
 
-    fn render_impl_item(&self, item: &Item) -> String {
+    fn render_impl_item(&self, parent_path: &str, item: &Item) -> String {
         match &item.inner {
-            ItemEnum::Function(_) => self.render_function(item, false),
+            ItemEnum::Function(_) => self.render_function(parent_path, item, false),
             ItemEnum::Constant { .. } => self.render_constant(item),
             ItemEnum::AssocType { .. } => render_associated_type(item),
             ItemEnum::TypeAlias(_) => self.render_type_alias(item),
@@ -405,7 +895,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
     }
 
     fn render_enum(&self, item: &Item) -> String {
-        let mut output = docs(item);
+        let mut output = self.item_docs(item);
 
         let enum_ = extract_item!(item, ItemEnum::Enum);
 
@@ -422,7 +912,9 @@ impl<'a, 'b> RenderState<'a, 'b> {
 
         for variant_id in &enum_.variants {
             let variant_item = must_get(self.crate_data, variant_id);
-            output.push_str(&self.render_enum_variant(variant_item));
+            if !self.is_hidden(variant_item) {
+                output.push_str(&self.render_enum_variant(variant_item));
+            }
         }
 
         output.push_str("}\n\n");
@@ -431,7 +923,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
     }
 
     fn render_enum_variant(&self, item: &Item) -> String {
-        let mut output = docs(item);
+        let mut output = self.item_docs(item);
 
         let variant = extract_item!(item, ItemEnum::Variant);
 
@@ -471,8 +963,8 @@ impl<'a, 'b> RenderState<'a, 'b> {
         output
     }
 
-    fn render_trait(&self, item: &Item) -> String {
-        let mut output = docs(item);
+    fn render_trait(&self, module_path: &str, item: &Item) -> String {
+        let mut output = self.item_docs(item);
 
         let trait_ = extract_item!(item, ItemEnum::Trait);
 
@@ -497,9 +989,16 @@ impl<'a, 'b> RenderState<'a, 'b> {
             where_clause
         ));
 
-        for item_id in &trait_.items {
-            let item = must_get(self.crate_data, item_id);
-            output.push_str(&self.render_trait_item(item));
+        let trait_path = item_path(module_path, item);
+        let mut items: Vec<&Item> = trait_
+            .items
+            .iter()
+            .map(|id| must_get(self.crate_data, id))
+            .filter(|item| !self.is_hidden(item))
+            .collect();
+        self.sort_items(&mut items, span_file(item));
+        for item in items {
+            output.push_str(&self.render_trait_item(&trait_path, item));
         }
 
         output.push_str("}\n\n");
@@ -507,9 +1006,9 @@ impl<'a, 'b> RenderState<'a, 'b> {
         output
     }
 
-    fn render_trait_item(&self, item: &Item) -> String {
+    fn render_trait_item(&self, trait_path: &str, item: &Item) -> String {
         match &item.inner {
-            ItemEnum::Function(_) => self.render_function(item, true),
+            ItemEnum::Function(_) => self.render_function(trait_path, item, true),
             ItemEnum::AssocConst { type_, default } => {
                 let default_str = default
                     .as_ref()
@@ -549,8 +1048,8 @@ impl<'a, 'b> RenderState<'a, 'b> {
         }
     }
 
-    fn render_struct(&self, item: &Item) -> String {
-        let mut output = docs(item);
+    fn render_struct(&self, module_path: &str, item: &Item) -> String {
+        let mut output = self.item_docs(item);
 
         let struct_ = extract_item!(item, ItemEnum::Struct);
 
@@ -574,7 +1073,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
                         field.as_ref().map(|id| {
                             let field_item = must_get(self.crate_data, id);
                             let ty = extract_item!(field_item, ItemEnum::StructField);
-                            if !self.is_visible(field_item) {
+                            if !self.is_visible(field_item) || self.is_hidden(field_item) {
                                 "_".to_string()
                             } else {
                                 format!("{}{}", render_vis(field_item), render_type(ty))
@@ -608,12 +1107,22 @@ impl<'a, 'b> RenderState<'a, 'b> {
             }
         }
 
+        if !self.struct_impls {
+            return output;
+        }
+
         // Render impl blocks
-        for impl_id in &struct_.impls {
-            let impl_item = must_get(self.crate_data, impl_id);
+        let struct_path = item_path(module_path, item);
+        let mut impls: Vec<&Item> = struct_
+            .impls
+            .iter()
+            .map(|id| must_get(self.crate_data, id))
+            .collect();
+        self.sort_items(&mut impls, span_file(item));
+        for impl_item in impls {
             let impl_ = extract_item!(impl_item, ItemEnum::Impl);
             if self.should_render_impl(impl_) {
-                output.push_str(&self.render_impl(impl_item));
+                output.push_str(&self.render_impl(&struct_path, impl_item));
             }
         }
 
@@ -622,10 +1131,12 @@ impl<'a, 'b> RenderState<'a, 'b> {
 
     fn render_struct_field(&self, field_id: &Id, force: bool) -> String {
         let field_item = must_get(self.crate_data, field_id);
-        if force || self.is_visible(field_item) {
+        if force || (self.is_visible(field_item) && !self.is_hidden(field_item)) {
             let ty = extract_item!(field_item, ItemEnum::StructField);
             format!(
-                "{}{}: {},\n",
+                "{}{}{}{}: {},\n",
+                self.render_source_location(field_item),
+                render_deprecation(field_item),
                 render_vis(field_item),
                 render_name(field_item),
                 render_type(ty)
@@ -636,7 +1147,7 @@ impl<'a, 'b> RenderState<'a, 'b> {
     }
 
     fn render_constant(&self, item: &Item) -> String {
-        let mut output = docs(item);
+        let mut output = self.item_docs(item);
 
         let (type_, const_) = extract_item!(item, ItemEnum::Constant { type_, const_ });
         output.push_str(&format!(
@@ -650,13 +1161,24 @@ impl<'a, 'b> RenderState<'a, 'b> {
         output
     }
 
-    fn render_module(&mut self, module_path: &str, item: &Item) -> String {
-        let module_path = if module_path.is_empty() {
+    fn render_module(&mut self, parent_path: &str, item: &Item) -> String {
+        let module_path = if parent_path.is_empty() {
             render_name(item).to_string()
         } else {
-            format!("{}::{}", module_path, render_name(item))
+            format!("{}::{}", parent_path, render_name(item))
+        };
+        // The crate root spans its whole file, so only nested modules are annotated.
+        let mut output = if parent_path.is_empty() {
+            String::new()
+        } else {
+            self.render_source_location(item)
         };
-        let mut output = format!("{}mod {} {{\n", render_vis(item), render_name(item));
+        output.push_str(&render_deprecation(item));
+        output.push_str(&format!(
+            "{}mod {} {{\n",
+            render_vis(item),
+            render_name(item)
+        ));
         // Add module doc comment if present
         if self.should_module_doc(&module_path, item) {
             if let Some(docs) = &item.docs {
@@ -669,17 +1191,40 @@ impl<'a, 'b> RenderState<'a, 'b> {
 
         let module = extract_item!(item, ItemEnum::Module);
 
-        for item_id in &module.items {
-            let item = must_get(self.crate_data, item_id);
-            output.push_str(&self.render_item(&module_path, item, false));
+        let mut items: Vec<&Item> = module
+            .items
+            .iter()
+            .map(|id| must_get(self.crate_data, id))
+            .collect();
+        self.sort_items(&mut items, span_file(item));
+        let (current, deprecated): (Vec<&Item>, Vec<&Item>) =
+            if self.config.deprecated == Deprecated::Last {
+                items.into_iter().partition(|i| !self.is_deprecated(i))
+            } else {
+                (items, Vec::new())
+            };
+
+        let mut body = String::new();
+        for item in current.into_iter().chain(deprecated) {
+            body.push_str(&self.render_item(&module_path, item, false));
+        }
+
+        // A module on the way to a pattern match, or one with no items of the selected kinds, is
+        // dropped if it ended up containing nothing
+        if body.is_empty()
+            && (self.filter_match(parent_path, item) == FilterMatch::Prefix
+                || !self.kind_selected(ItemKind::Module))
+        {
+            return String::new();
         }
 
+        output.push_str(&body);
         output.push_str("}\n\n");
         output
     }
 
-    fn render_function(&self, item: &Item, is_trait_method: bool) -> String {
-        let mut output = docs(item);
+    fn render_function(&self, parent_path: &str, item: &Item, is_trait_method: bool) -> String {
+        let mut output = self.item_docs(item);
         let function = extract_item!(item, ItemEnum::Function);
 
         // Handle const, async, and unsafe keywords in the correct order
@@ -708,6 +1253,8 @@ impl<'a, 'b> RenderState<'a, 'b> {
         // Use semicolon for trait method declarations, empty body for implementations
         if is_trait_method && !function.has_body {
             output.push_str(";\n\n");
+        } else if let Some(body) = self.expanded_body(parent_path, item) {
+            output.push_str(&format!(" {}\n\n", body));
         } else {
             output.push_str(" {}\n\n");
         }
@@ -0,0 +1,313 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cargo::{
+    core::{Package, SourceId, Target, Workspace},
+    ops,
+    util::context::GlobalContext,
+};
+
+use crate::error::{Result, RuskelError};
+
+/// The toolchain used to generate rustdoc JSON, which is a nightly-only feature.
+const TOOLCHAIN: &str = "nightly";
+
+/// The crate within a package to document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CrateTarget {
+    /// The library target.
+    #[default]
+    Lib,
+    /// The binary target with the given name.
+    Bin(String),
+    /// The example with the given name.
+    Example(String),
+}
+
+impl fmt::Display for CrateTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrateTarget::Lib => write!(f, "library target"),
+            CrateTarget::Bin(name) => write!(f, "binary target {}", name),
+            CrateTarget::Example(name) => write!(f, "example {}", name),
+        }
+    }
+}
+
+/// Builds rustdoc JSON for a package by running `cargo rustdoc` on the nightly toolchain, and
+/// returns the path to the generated JSON file.
+#[derive(Debug, Default)]
+pub struct RustdocBuilder {
+    manifest_path: PathBuf,
+    target: CrateTarget,
+    dependent: Option<PathBuf>,
+    no_default_features: bool,
+    all_features: bool,
+    features: Vec<String>,
+    offline: bool,
+    config: Vec<String>,
+    document_private_items: bool,
+    document_hidden_items: bool,
+}
+
+impl RustdocBuilder {
+    pub fn new(manifest_path: &Path) -> Self {
+        RustdocBuilder {
+            manifest_path: manifest_path.to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    /// Selects the crate to document. Defaults to the library.
+    pub fn target(mut self, target: &CrateTarget) -> Self {
+        self.target = target.clone();
+        self
+    }
+
+    /// Documents the package as a dependency of the crate with the given manifest, building from
+    /// that crate with `-p`. Feature flags are then ignored, since features are selected in the
+    /// dependency declaration.
+    pub fn dependent(mut self, manifest_path: Option<&Path>) -> Self {
+        self.dependent = manifest_path.map(Path::to_path_buf);
+        self
+    }
+
+    pub fn no_default_features(mut self, value: bool) -> Self {
+        self.no_default_features = value;
+        self
+    }
+
+    pub fn all_features(mut self, value: bool) -> Self {
+        self.all_features = value;
+        self
+    }
+
+    pub fn features(mut self, features: &[String]) -> Self {
+        self.features = features.to_vec();
+        self
+    }
+
+    pub fn offline(mut self, value: bool) -> Self {
+        self.offline = value;
+        self
+    }
+
+    /// Passes `--config` values to cargo, e.g. to replace crates.io with a vendored source.
+    pub fn config(mut self, config: &[String]) -> Self {
+        self.config = config.to_vec();
+        self
+    }
+
+    pub fn document_private_items(mut self, value: bool) -> Self {
+        self.document_private_items = value;
+        self
+    }
+
+    /// Includes items marked `#[doc(hidden)]`, which rustdoc otherwise strips from its output.
+    pub fn document_hidden_items(mut self, value: bool) -> Self {
+        self.document_hidden_items = value;
+        self
+    }
+
+    fn command(&self, package_name: &str) -> Command {
+        let mut cmd = Command::new("rustup");
+        cmd.args(["run", TOOLCHAIN, "cargo", "rustdoc"]);
+        match &self.target {
+            CrateTarget::Lib => {
+                cmd.arg("--lib");
+            }
+            CrateTarget::Bin(name) => {
+                cmd.args(["--bin", name]);
+            }
+            CrateTarget::Example(name) => {
+                cmd.args(["--example", name]);
+            }
+        }
+        match &self.dependent {
+            Some(dependent) => {
+                cmd.arg("--manifest-path").arg(dependent);
+                cmd.args(["-p", package_name]);
+            }
+            None => {
+                cmd.arg("--manifest-path").arg(&self.manifest_path);
+                if self.no_default_features {
+                    cmd.arg("--no-default-features");
+                }
+                if self.all_features {
+                    cmd.arg("--all-features");
+                }
+                for feature in &self.features {
+                    cmd.args(["--features", feature]);
+                }
+            }
+        }
+        if self.offline {
+            cmd.arg("--offline");
+        }
+        for config in &self.config {
+            cmd.args(["--config", config]);
+        }
+        cmd.args(["--", "-Z", "unstable-options", "--output-format", "json"]);
+        if self.document_private_items {
+            cmd.arg("--document-private-items");
+        }
+        if self.document_hidden_items {
+            cmd.arg("--document-hidden-items");
+        }
+        cmd
+    }
+
+    /// Reads the package on its own, without loading the workspace around it, which a package
+    /// documented through a dependent isn't part of.
+    fn read_package(&self, config: &GlobalContext) -> Result<Package> {
+        let source_id = SourceId::for_path(self.manifest_path.parent().unwrap())
+            .map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        ops::read_package(&self.manifest_path, source_id, config)
+            .map_err(|e| RuskelError::Cargo(e.to_string()))
+    }
+
+    /// Finds the selected target in the package, listing the available targets if it's missing.
+    fn find_target<'a>(&self, package: &'a Package) -> Result<&'a Target> {
+        let found = package.targets().iter().find(|t| match &self.target {
+            CrateTarget::Lib => t.is_lib(),
+            CrateTarget::Bin(name) => t.is_bin() && t.name() == name,
+            CrateTarget::Example(name) => t.is_example() && t.name() == name,
+        });
+        found.ok_or_else(|| {
+            let available: Vec<String> = package
+                .targets()
+                .iter()
+                .filter_map(|t| {
+                    let kind = if t.is_lib() {
+                        "lib"
+                    } else if t.is_bin() {
+                        "bin"
+                    } else if t.is_example() {
+                        "example"
+                    } else {
+                        return None;
+                    };
+                    Some(format!("{} {}", kind, t.name()))
+                })
+                .collect();
+            RuskelError::Generate(format!(
+                "package {} has no {}, available targets: {}",
+                package.name(),
+                self.target,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            ))
+        })
+    }
+
+    /// Returns the path cargo writes the JSON output to: `<target-dir>/doc/<crate-name>.json`.
+    fn json_path(&self, config: &GlobalContext, target: &Target) -> Result<PathBuf> {
+        let root = self.dependent.as_ref().unwrap_or(&self.manifest_path);
+        let workspace =
+            Workspace::new(root, config).map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let mut path = workspace.target_dir().into_path_unlocked();
+        path.push("doc");
+        path.push(target.crate_name());
+        path.set_extension("json");
+        Ok(path)
+    }
+
+    /// Builds rustdoc JSON for the libraries of several workspace members with a single
+    /// `cargo doc` invocation, and returns the path of each package's JSON file, in order. The
+    /// builder's manifest is the workspace's root manifest, and the target selection and
+    /// dependent are ignored.
+    pub fn build_workspace(self, packages: &[String]) -> Result<Vec<PathBuf>> {
+        let config = GlobalContext::default().map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let workspace = Workspace::new(&self.manifest_path, &config)
+            .map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let mut doc_dir = workspace.target_dir().into_path_unlocked();
+        doc_dir.push("doc");
+
+        let mut json_paths = Vec::new();
+        for name in packages {
+            let package = workspace
+                .members()
+                .find(|p| p.name().as_str() == name)
+                .ok_or_else(|| {
+                    RuskelError::ModuleNotFound(format!("no workspace member named {}", name))
+                })?;
+            let lib = package
+                .targets()
+                .iter()
+                .find(|t| t.is_lib())
+                .ok_or_else(|| {
+                    RuskelError::Generate(format!("package {} has no library target", name))
+                })?;
+            json_paths.push(doc_dir.join(format!("{}.json", lib.crate_name())));
+        }
+
+        let mut rustdoc_flags = vec!["-Z", "unstable-options", "--output-format", "json"];
+        if self.document_private_items {
+            rustdoc_flags.push("--document-private-items");
+        }
+        if self.document_hidden_items {
+            rustdoc_flags.push("--document-hidden-items");
+        }
+        let rustdoc_flags = match std::env::var("RUSTDOCFLAGS") {
+            Ok(existing) if !existing.is_empty() => {
+                format!("{} {}", existing, rustdoc_flags.join(" "))
+            }
+            _ => rustdoc_flags.join(" "),
+        };
+
+        let mut cmd = Command::new("rustup");
+        cmd.args(["run", TOOLCHAIN, "cargo", "doc", "--no-deps", "--lib"]);
+        cmd.arg("--manifest-path").arg(&self.manifest_path);
+        for name in packages {
+            cmd.args(["-p", name]);
+        }
+        if self.no_default_features {
+            cmd.arg("--no-default-features");
+        }
+        if self.all_features {
+            cmd.arg("--all-features");
+        }
+        for feature in &self.features {
+            cmd.args(["--features", feature]);
+        }
+        if self.offline {
+            cmd.arg("--offline");
+        }
+        for config in &self.config {
+            cmd.args(["--config", config]);
+        }
+        cmd.env("RUSTDOCFLAGS", rustdoc_flags);
+
+        let status = cmd
+            .status()
+            .map_err(|e| RuskelError::Generate(format!("failed to run rustup: {}", e)))?;
+        if !status.success() {
+            return Err(RuskelError::Generate(format!(
+                "cargo doc failed for {}",
+                self.manifest_path.display()
+            )));
+        }
+        Ok(json_paths)
+    }
+
+    pub fn build(self) -> Result<PathBuf> {
+        let config = GlobalContext::default().map_err(|e| RuskelError::Cargo(e.to_string()))?;
+        let package = self.read_package(&config)?;
+        let json_path = self.json_path(&config, self.find_target(&package)?)?;
+        let status = self
+            .command(package.name().as_str())
+            .status()
+            .map_err(|e| RuskelError::Generate(format!("failed to run rustup: {}", e)))?;
+        if !status.success() {
+            return Err(RuskelError::Generate(format!(
+                "cargo rustdoc failed for {}",
+                self.manifest_path.display()
+            )));
+        }
+        Ok(json_path)
+    }
+}
@@ -0,0 +1,43 @@
+//! Finds items in a crate by name.
+
+use rustdoc_types::{Crate, Visibility};
+
+use crate::filter::ItemKind;
+
+/// An item found by [`Ruskel::search`](crate::Ruskel::search).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The path of the item where it's defined, e.g. `serde::de::Deserialize`.
+    pub path: String,
+    pub kind: ItemKind,
+}
+
+/// Returns the crate's own items matching the query, sorted by path. Queries containing `::` are
+/// matched against the full path, others against the item name, ignoring case.
+pub fn search(crate_data: &Crate, query: &str, private_items: bool) -> Vec<SearchResult> {
+    let query = query.to_lowercase();
+    let mut results: Vec<SearchResult> = crate_data
+        .paths
+        .iter()
+        .filter(|(_, summary)| summary.crate_id == 0)
+        .filter_map(|(id, summary)| {
+            let item = crate_data.index.get(id)?;
+            if !private_items && !matches!(item.visibility, Visibility::Public) {
+                return None;
+            }
+            let kind = ItemKind::of(item)?;
+            let path = summary.path.join("::");
+            let haystack = if query.contains("::") {
+                path.as_str()
+            } else {
+                summary.path.last()?.as_str()
+            };
+            haystack
+                .to_lowercase()
+                .contains(&query)
+                .then_some(SearchResult { path, kind })
+        })
+        .collect();
+    results.sort_by(|a, b| a.path.cmp(&b.path));
+    results
+}
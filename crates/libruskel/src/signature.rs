@@ -0,0 +1,313 @@
+//! Collects the items that an item's signature refers to.
+
+use rustdoc_types::{
+    Crate, FnDecl, GenericArg, GenericArgs, GenericBound, GenericParamDef, GenericParamDefKind,
+    Generics, Id, Item, ItemEnum, Path, StructKind, Term, Type, TypeBindingKind, VariantKind,
+    Visibility, WherePredicate,
+};
+
+/// A type or trait an item's signature refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureLink {
+    pub id: Id,
+    /// The path the item is defined at, e.g. `alloc::string::String`.
+    pub path: String,
+    /// Whether the item is defined in this crate.
+    pub local: bool,
+}
+
+/// Returns the ids of the types and traits named in the item's own signature, in order of first
+/// appearance. Fields, methods and impls are items of their own and aren't included, except that
+/// a variant's signature covers its fields, and an impl's covers the trait and the implementing
+/// type. Ids may refer to other crates, in which case they're only found in `Crate::paths`.
+pub(crate) fn signature_paths(crate_data: &Crate, item: &Item) -> Vec<Id> {
+    let mut collector = Collector::default();
+    match &item.inner {
+        ItemEnum::Function(function) => {
+            collector.generics(&function.generics);
+            collector.decl(&function.decl);
+        }
+        ItemEnum::Struct(struct_) => collector.generics(&struct_.generics),
+        ItemEnum::Union(union_) => collector.generics(&union_.generics),
+        ItemEnum::Enum(enum_) => collector.generics(&enum_.generics),
+        ItemEnum::Variant(variant) => {
+            let fields: Vec<&Id> = match &variant.kind {
+                VariantKind::Plain => Vec::new(),
+                VariantKind::Tuple(fields) => fields.iter().flatten().collect(),
+                VariantKind::Struct { fields, .. } => fields.iter().collect(),
+            };
+            for field in fields.into_iter().filter_map(|id| crate_data.index.get(id)) {
+                if let ItemEnum::StructField(ty) = &field.inner {
+                    collector.type_(ty);
+                }
+            }
+        }
+        ItemEnum::StructField(ty) => collector.type_(ty),
+        ItemEnum::Trait(trait_) => {
+            collector.generics(&trait_.generics);
+            collector.bounds(&trait_.bounds);
+        }
+        ItemEnum::TraitAlias(alias) => {
+            collector.generics(&alias.generics);
+            collector.bounds(&alias.params);
+        }
+        ItemEnum::Impl(impl_) => {
+            collector.generics(&impl_.generics);
+            if let Some(trait_) = &impl_.trait_ {
+                collector.path(trait_);
+            }
+            collector.type_(&impl_.for_);
+        }
+        ItemEnum::TypeAlias(alias) => {
+            collector.generics(&alias.generics);
+            collector.type_(&alias.type_);
+        }
+        ItemEnum::OpaqueTy(opaque) => {
+            collector.generics(&opaque.generics);
+            collector.bounds(&opaque.bounds);
+        }
+        ItemEnum::Constant { type_, .. } | ItemEnum::AssocConst { type_, .. } => {
+            collector.type_(type_)
+        }
+        ItemEnum::Static(static_) => collector.type_(&static_.type_),
+        ItemEnum::AssocType {
+            generics,
+            bounds,
+            default,
+        } => {
+            collector.generics(generics);
+            collector.bounds(bounds);
+            if let Some(default) = default {
+                collector.type_(default);
+            }
+        }
+        _ => {}
+    }
+    collector.ids
+}
+
+/// Returns the types and traits named in the signature of the item with the given id, followed by
+/// those named by its fields, variants or trait items, which belong to its definition. Private
+/// members are only followed if `private_items` is set. Re-exports refer to the item they
+/// re-export. Each is listed once, and only if its path is known.
+pub(crate) fn signature_links(
+    crate_data: &Crate,
+    id: &Id,
+    private_items: bool,
+) -> Vec<SignatureLink> {
+    let Some(item) = crate_data.index.get(id) else {
+        return Vec::new();
+    };
+    let mut ids = match &item.inner {
+        ItemEnum::Import(import) => import.id.iter().cloned().collect(),
+        _ => signature_paths(crate_data, item),
+    };
+    let members: Vec<&Id> = match &item.inner {
+        ItemEnum::Struct(struct_) => match &struct_.kind {
+            StructKind::Unit => Vec::new(),
+            StructKind::Tuple(fields) => fields.iter().flatten().collect(),
+            StructKind::Plain { fields, .. } => fields.iter().collect(),
+        },
+        ItemEnum::Union(union_) => union_.fields.iter().collect(),
+        ItemEnum::Enum(enum_) => enum_.variants.iter().collect(),
+        ItemEnum::Trait(trait_) => trait_.items.iter().collect(),
+        _ => Vec::new(),
+    };
+    for member in members
+        .into_iter()
+        .filter_map(|id| crate_data.index.get(id))
+    {
+        if private_items || matches!(member.visibility, Visibility::Public | Visibility::Default) {
+            ids.extend(signature_paths(crate_data, member));
+        }
+    }
+
+    let mut links: Vec<SignatureLink> = Vec::new();
+    for link in ids {
+        if link == *id || links.iter().any(|l| l.id == link) {
+            continue;
+        }
+        let Some(summary) = crate_data.paths.get(&link) else {
+            continue;
+        };
+        links.push(SignatureLink {
+            path: summary.path.join("::"),
+            local: summary.crate_id == 0,
+            id: link,
+        });
+    }
+    links
+}
+
+/// Returns the ids of the types and traits named in a type, in order of first appearance. If
+/// `self_type` is given, `Self` counts as naming it.
+pub(crate) fn type_paths(ty: &Type, self_type: Option<&Id>) -> Vec<Id> {
+    let mut collector = Collector {
+        self_type: self_type.cloned(),
+        ..Collector::default()
+    };
+    collector.type_(ty);
+    collector.ids
+}
+
+#[derive(Default)]
+struct Collector {
+    ids: Vec<Id>,
+    /// The type that `Self` refers to, if known.
+    self_type: Option<Id>,
+}
+
+impl Collector {
+    fn id(&mut self, id: &Id) {
+        if !self.ids.contains(id) {
+            self.ids.push(id.clone());
+        }
+    }
+
+    fn path(&mut self, path: &Path) {
+        self.id(&path.id);
+        if let Some(args) = &path.args {
+            self.generic_args(args);
+        }
+    }
+
+    fn generics(&mut self, generics: &Generics) {
+        self.generic_params(&generics.params);
+        for predicate in &generics.where_predicates {
+            match predicate {
+                WherePredicate::BoundPredicate {
+                    type_,
+                    bounds,
+                    generic_params,
+                } => {
+                    self.type_(type_);
+                    self.bounds(bounds);
+                    self.generic_params(generic_params);
+                }
+                WherePredicate::LifetimePredicate { .. } => {}
+                WherePredicate::EqPredicate { lhs, rhs } => {
+                    self.type_(lhs);
+                    self.term(rhs);
+                }
+            }
+        }
+    }
+
+    fn generic_params(&mut self, params: &[GenericParamDef]) {
+        for param in params {
+            match &param.kind {
+                GenericParamDefKind::Lifetime { .. } => {}
+                GenericParamDefKind::Type {
+                    bounds, default, ..
+                } => {
+                    self.bounds(bounds);
+                    if let Some(default) = default {
+                        self.type_(default);
+                    }
+                }
+                GenericParamDefKind::Const { type_, .. } => self.type_(type_),
+            }
+        }
+    }
+
+    fn bounds(&mut self, bounds: &[GenericBound]) {
+        for bound in bounds {
+            if let GenericBound::TraitBound {
+                trait_,
+                generic_params,
+                ..
+            } = bound
+            {
+                self.path(trait_);
+                self.generic_params(generic_params);
+            }
+        }
+    }
+
+    fn decl(&mut self, decl: &FnDecl) {
+        for (_, ty) in &decl.inputs {
+            self.type_(ty);
+        }
+        if let Some(output) = &decl.output {
+            self.type_(output);
+        }
+    }
+
+    fn generic_args(&mut self, args: &GenericArgs) {
+        match args {
+            GenericArgs::AngleBracketed { args, bindings } => {
+                for arg in args {
+                    if let GenericArg::Type(ty) = arg {
+                        self.type_(ty);
+                    }
+                }
+                for binding in bindings {
+                    self.generic_args(&binding.args);
+                    match &binding.binding {
+                        TypeBindingKind::Equality(term) => self.term(term),
+                        TypeBindingKind::Constraint(bounds) => self.bounds(bounds),
+                    }
+                }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                for ty in inputs {
+                    self.type_(ty);
+                }
+                if let Some(output) = output {
+                    self.type_(output);
+                }
+            }
+        }
+    }
+
+    fn term(&mut self, term: &Term) {
+        if let Term::Type(ty) = term {
+            self.type_(ty);
+        }
+    }
+
+    fn type_(&mut self, ty: &Type) {
+        match ty {
+            Type::ResolvedPath(path) => self.path(path),
+            Type::DynTrait(dyn_trait) => {
+                for poly_trait in &dyn_trait.traits {
+                    self.path(&poly_trait.trait_);
+                    self.generic_params(&poly_trait.generic_params);
+                }
+            }
+            Type::FunctionPointer(f) => {
+                self.generic_params(&f.generic_params);
+                self.decl(&f.decl);
+            }
+            Type::Tuple(types) => {
+                for ty in types {
+                    self.type_(ty);
+                }
+            }
+            Type::Slice(ty) => self.type_(ty),
+            Type::Array { type_, .. }
+            | Type::Pat { type_, .. }
+            | Type::RawPointer { type_, .. }
+            | Type::BorrowedRef { type_, .. } => self.type_(type_),
+            Type::ImplTrait(bounds) => self.bounds(bounds),
+            Type::QualifiedPath {
+                args,
+                self_type,
+                trait_,
+                ..
+            } => {
+                self.generic_args(args);
+                self.type_(self_type);
+                if let Some(trait_) = trait_ {
+                    self.path(trait_);
+                }
+            }
+            Type::Generic(name) if name == "Self" => {
+                if let Some(self_type) = self.self_type.clone() {
+                    self.id(&self_type);
+                }
+            }
+            Type::Generic(_) | Type::Primitive(_) | Type::Infer => {}
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! API snapshots: rendered skeletons stored in a file and compared against the current API.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Result, RuskelError};
+use crate::render::Renderer;
+use crate::Ruskel;
+
+/// The environment variable that makes [`assert_api_snapshot!`](crate::assert_api_snapshot)
+/// write snapshots instead of checking them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "RUSKEL_UPDATE_SNAPSHOTS";
+
+/// Lines of unchanged context shown around each change in a diff.
+const CONTEXT: usize = 3;
+
+/// Normalizes rendered output for storage: trailing whitespace is removed from each line, and
+/// the output ends with exactly one newline.
+pub(crate) fn normalize(rendered: &str) -> String {
+    let mut output: String = rendered
+        .lines()
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect();
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+    output
+}
+
+/// Writes an API snapshot, creating parent directories as needed.
+pub fn write_snapshot(path: &Path, api: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, api)?;
+    Ok(())
+}
+
+/// Compares an API against a stored snapshot, failing with a diff if they differ.
+pub fn check_snapshot(path: &Path, api: &str) -> Result<()> {
+    let stored = match fs::read_to_string(path) {
+        Ok(stored) => stored,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(RuskelError::SnapshotNotFound(path.display().to_string()))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if normalize(&stored) == api {
+        return Ok(());
+    }
+    Err(RuskelError::SnapshotMismatch {
+        path: path.display().to_string(),
+        diff: diff(&normalize(&stored), api),
+    })
+}
+
+/// Renders the public API of a target and checks it against a snapshot, or writes the snapshot
+/// if the `RUSKEL_UPDATE_SNAPSHOTS` environment variable is set. This is what
+/// [`assert_api_snapshot!`](crate::assert_api_snapshot) calls.
+pub fn check_api_snapshot(target: &str, path: &Path) -> Result<()> {
+    let api = Ruskel::new(target).snapshot(Renderer::default())?;
+    if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+        write_snapshot(path, &api)
+    } else {
+        check_snapshot(path, &api)
+    }
+}
+
+/// Returns a unified diff from `expected` to `actual`, or an empty string if they're equal.
+pub(crate) fn diff(expected: &str, actual: &str) -> String {
+    // Both texts end with a newline, which would otherwise show up as an empty last line
+    let lines = diff::lines(
+        expected.strip_suffix('\n').unwrap_or(expected),
+        actual.strip_suffix('\n').unwrap_or(actual),
+    );
+
+    // The line numbers in each text that each entry of the diff starts at
+    let mut positions = Vec::with_capacity(lines.len());
+    let (mut old, mut new) = (1, 1);
+    for line in &lines {
+        positions.push((old, new));
+        match line {
+            diff::Result::Left(_) => old += 1,
+            diff::Result::Right(_) => new += 1,
+            diff::Result::Both(..) => {
+                old += 1;
+                new += 1;
+            }
+        }
+    }
+
+    // Changes with their context, merged where they overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if matches!(line, diff::Result::Both(..)) {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut output = String::new();
+    for (start, end) in hunks {
+        let hunk = &lines[start..end];
+        let removed = hunk
+            .iter()
+            .filter(|l| !matches!(l, diff::Result::Right(_)))
+            .count();
+        let added = hunk
+            .iter()
+            .filter(|l| !matches!(l, diff::Result::Left(_)))
+            .count();
+        let (old, new) = positions[start];
+        output.push_str(&format!("@@ -{},{} +{},{} @@\n", old, removed, new, added));
+        for line in hunk {
+            let (prefix, text) = match line {
+                diff::Result::Left(text) => ('-', text),
+                diff::Result::Right(text) => ('+', text),
+                diff::Result::Both(text, _) => (' ', text),
+            };
+            output.push_str(&format!("{}{}\n", prefix, text));
+        }
+    }
+    output
+}
+
+/// Asserts that the public API of the crate being tested matches a snapshot file, given relative
+/// to the crate's manifest directory. Run the tests with `RUSKEL_UPDATE_SNAPSHOTS=1` to write or
+/// update the snapshot. A target in any form accepted by [`Ruskel::new`] can be given first to
+/// check a different crate.
+///
+/// ```no_run
+/// #[test]
+/// fn public_api() {
+///     libruskel::assert_api_snapshot!("tests/api.rs.snap");
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_api_snapshot {
+    ($snapshot:expr) => {
+        $crate::assert_api_snapshot!(env!("CARGO_MANIFEST_DIR"), $snapshot)
+    };
+    ($target:expr, $snapshot:expr) => {
+        if let Err(e) = $crate::check_api_snapshot(
+            $target,
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($snapshot),
+        ) {
+            panic!("{}", e);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("pub fn f() {}  \n\n\n"), "pub fn f() {}\n");
+        assert_eq!(normalize(""), "\n");
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), "");
+        let expected: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+        let actual = expected
+            .replace("line 2\n", "line two\n")
+            .replace("line 9\n", "");
+        assert_eq!(
+            diff(&expected, &actual),
+            "@@ -1,10 +1,9 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n \
+             line 6\n line 7\n line 8\n-line 9\n line 10\n"
+        );
+        let actual = expected.replace("line 1\n", "");
+        assert_eq!(
+            diff(&expected, &actual),
+            "@@ -1,4 +1,3 @@\n-line 1\n line 2\n line 3\n line 4\n"
+        );
+    }
+}
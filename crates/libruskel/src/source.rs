@@ -0,0 +1,201 @@
+//! Helpers for recovering original source text from rustdoc spans.
+
+use rustdoc_types::Span;
+
+/// Returns the text covered by a span. Span lines are 1-based, columns are 0-based character
+/// offsets, and the end is exclusive.
+pub fn span_text(source: &str, span: &Span) -> Option<String> {
+    let (begin_line, begin_col) = span.begin;
+    let (end_line, end_col) = span.end;
+    if begin_line == 0 || end_line < begin_line {
+        return None;
+    }
+    let lines: Vec<&str> = source
+        .lines()
+        .skip(begin_line - 1)
+        .take(end_line - begin_line + 1)
+        .collect();
+    if lines.len() != end_line - begin_line + 1 {
+        return None;
+    }
+
+    let last = lines.len() - 1;
+    let mut output = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        let start = if i == 0 { begin_col } else { 0 };
+        let end = if i == last {
+            end_col
+        } else {
+            line.chars().count()
+        };
+        if start > end {
+            return None;
+        }
+        output.extend(line.chars().skip(start).take(end - start));
+        if i != last {
+            output.push('\n');
+        }
+    }
+    Some(output)
+}
+
+/// Returns the body of a function, braces included, given the source text of the whole function
+/// item. The body starts at the first brace outside of any parentheses, brackets or generic
+/// arguments, skipping comments, strings and character literals in the signature.
+pub fn function_body(item_source: &str) -> Option<&str> {
+    let chars: Vec<(usize, char)> = item_source.char_indices().collect();
+    let mut depth = 0usize;
+    // Generic argument depth, only tracked outside other delimiters, where `<` and `>` can't be
+    // comparisons
+    let mut generics = 0usize;
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        match c {
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i].1 != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len()
+                    && !(chars[i].1 == '*' && chars.get(i + 1).map(|c| c.1) == Some('/'))
+                {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i].1 != '"' {
+                    if chars[i].1 == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            'r' if matches!(next, Some('"') | Some('#'))
+                && (i == 0 || !is_ident_char(chars[i - 1].1)) =>
+            {
+                let hashes = chars[i + 1..].iter().take_while(|c| c.1 == '#').count();
+                if chars.get(i + 1 + hashes).map(|c| c.1) == Some('"') {
+                    i += hashes + 2;
+                    while i < chars.len() {
+                        if chars[i].1 == '"'
+                            && chars[i + 1..].iter().take_while(|c| c.1 == '#').count() >= hashes
+                        {
+                            i += hashes;
+                            break;
+                        }
+                        i += 1;
+                    }
+                }
+            }
+            '\'' => {
+                // Either a character literal or a lifetime, which has no closing quote. Escapes
+                // are skipped whole, so that `'\''` ends at its last quote.
+                if next == Some('\\') {
+                    i += 3;
+                    while i < chars.len() && chars[i].1 != '\'' {
+                        i += 1;
+                    }
+                } else if chars.get(i + 2).map(|c| c.1) == Some('\'') {
+                    i += 2;
+                }
+            }
+            '<' if depth == 0 => generics += 1,
+            '>' if depth == 0 && (i == 0 || chars[i - 1].1 != '-') => {
+                generics = generics.saturating_sub(1)
+            }
+            '{' if depth == 0 && generics == 0 => {
+                let body = item_source[offset..].trim_end();
+                return body.ends_with('}').then_some(body);
+            }
+            // Braces in generic arguments are const expressions, e.g. `Foo<{ N + 1 }>`
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn span(begin: (usize, usize), end: (usize, usize)) -> Span {
+        Span {
+            filename: PathBuf::from("src/lib.rs"),
+            begin,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_span_text() {
+        let source = "pub struct S;\nimpl S {\n    pub fn m(&self) -> u8 {\n        1\n    }\n}\n";
+        assert_eq!(
+            span_text(source, &span((1, 0), (1, 13))).as_deref(),
+            Some("pub struct S;")
+        );
+        assert_eq!(
+            span_text(source, &span((3, 4), (5, 5))).as_deref(),
+            Some("pub fn m(&self) -> u8 {\n        1\n    }")
+        );
+        assert_eq!(span_text(source, &span((3, 4), (9, 5))), None);
+        assert_eq!(span_text(source, &span((0, 0), (1, 5))), None);
+    }
+
+    #[test]
+    fn test_function_body() {
+        let cases = [
+            ("fn f() {}", Some("{}")),
+            ("fn f() -> u8 { 1 }", Some("{ 1 }")),
+            ("fn f(x: [u8; 2]) -> u8 { x[0] }", Some("{ x[0] }")),
+            ("fn f(_: impl Fn() -> ()) { g() }", Some("{ g() }")),
+            (
+                "fn f<'a>(x: &'a str) -> &'a str\nwhere\n    'a: 'static,\n{\n    x\n}",
+                Some("{\n    x\n}"),
+            ),
+            (
+                "#[cfg_attr(test, doc = \"{\")]\nfn f() /* { */ -> char { '{' }",
+                Some("{ '{' }"),
+            ),
+            ("#[doc = r#\"{\"#] fn f() { }", Some("{ }")),
+            (
+                "fn f() -> Pair<'\\'','{'> { Pair::new('\\'', '{') }",
+                Some("{ Pair::new('\\'', '{') }"),
+            ),
+            (
+                "fn f(x: [char; 2]) -> char { match x { ['\\'','{'] => '}', _ => '{' } }",
+                Some("{ match x { ['\\'','{'] => '}', _ => '{' } }"),
+            ),
+            (
+                "fn f<const N: usize>() -> Foo<{ N + 1 }> { Foo::new() }",
+                Some("{ Foo::new() }"),
+            ),
+            (
+                "fn f<const N: usize>() -> Foo<{ N > 1 }>\nwhere\n    [(); { N }]:,\n{ Foo }",
+                Some("{ Foo }"),
+            ),
+            (
+                "fn f(g: impl Fn(u8) -> Vec<u8>) -> impl Fn() -> u8 { || 1 }",
+                Some("{ || 1 }"),
+            ),
+            ("fn f();", None),
+            ("m!(f);", None),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(function_body(source), expected, "{}", source);
+        }
+    }
+}
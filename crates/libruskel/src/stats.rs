@@ -0,0 +1,234 @@
+//! Metrics describing the size and shape of a crate's public API.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rustdoc_types::{
+    Crate, GenericBound, GenericParamDefKind, Generics, Item, ItemEnum, WherePredicate,
+};
+use serde::Serialize;
+
+use crate::filter::ItemKind;
+use crate::render::Renderer;
+use crate::surface::Surface;
+
+/// API metrics for one module, or totals for a crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ModuleStats {
+    /// The module path, or `total` for crate totals.
+    pub path: String,
+    /// Public items in the module by kind, e.g. `struct` or `fn`. Re-exports of items from
+    /// private modules are counted as the item they refer to.
+    pub items: BTreeMap<String, usize>,
+    /// Public methods of the module's types and traits, excluding trait impls.
+    pub methods: usize,
+    /// Items and methods without doc comments.
+    pub undocumented: usize,
+    /// Functions, methods, types and traits, whose generics are counted below.
+    pub signatures: usize,
+    /// Type and const generic parameters, including `impl Trait` arguments.
+    pub generic_params: usize,
+    /// Trait bounds on generic parameters and in where clauses.
+    pub generic_bounds: usize,
+    /// Unsafe functions and methods.
+    pub unsafe_functions: usize,
+    /// Deprecated items and methods.
+    pub deprecated: usize,
+}
+
+impl ModuleStats {
+    fn add(&mut self, other: &ModuleStats) {
+        for (kind, count) in &other.items {
+            *self.items.entry(kind.clone()).or_default() += count;
+        }
+        self.methods += other.methods;
+        self.undocumented += other.undocumented;
+        self.signatures += other.signatures;
+        self.generic_params += other.generic_params;
+        self.generic_bounds += other.generic_bounds;
+        self.unsafe_functions += other.unsafe_functions;
+        self.deprecated += other.deprecated;
+    }
+
+    /// Counts an item's docs, deprecation and signature.
+    fn count(&mut self, item: &Item) {
+        if item
+            .docs
+            .as_deref()
+            .is_none_or(|docs| docs.trim().is_empty())
+        {
+            self.undocumented += 1;
+        }
+        if item.deprecation.is_some() {
+            self.deprecated += 1;
+        }
+        let generics = match &item.inner {
+            ItemEnum::Function(function) => {
+                if function.header.unsafe_ {
+                    self.unsafe_functions += 1;
+                }
+                &function.generics
+            }
+            ItemEnum::Struct(struct_) => &struct_.generics,
+            ItemEnum::Enum(enum_) => &enum_.generics,
+            ItemEnum::Union(union_) => &union_.generics,
+            ItemEnum::Trait(trait_) => &trait_.generics,
+            ItemEnum::TypeAlias(alias) => &alias.generics,
+            _ => return,
+        };
+        self.signatures += 1;
+        self.count_generics(generics);
+    }
+
+    fn count_generics(&mut self, generics: &Generics) {
+        let trait_bounds = |bounds: &[GenericBound]| {
+            bounds
+                .iter()
+                .filter(|b| matches!(b, GenericBound::TraitBound { .. }))
+                .count()
+        };
+        for param in &generics.params {
+            match &param.kind {
+                GenericParamDefKind::Lifetime { .. } => {}
+                GenericParamDefKind::Type { bounds, .. } => {
+                    self.generic_params += 1;
+                    self.generic_bounds += trait_bounds(bounds);
+                }
+                GenericParamDefKind::Const { .. } => self.generic_params += 1,
+            }
+        }
+        for predicate in &generics.where_predicates {
+            if let WherePredicate::BoundPredicate { bounds, .. } = predicate {
+                self.generic_bounds += trait_bounds(bounds);
+            }
+        }
+    }
+}
+
+/// API metrics for each public module of a crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiStats {
+    /// Modules in the order they're declared, starting with the crate root.
+    pub modules: Vec<ModuleStats>,
+    pub total: ModuleStats,
+}
+
+impl ApiStats {
+    /// Collects metrics for the public modules of a crate, or all modules if `private_items` is
+    /// set. Items marked `#[doc(hidden)]` aren't part of the API and are skipped.
+    pub fn new(crate_data: &Crate, private_items: bool) -> Self {
+        let renderer = Renderer::default().with_private_items(private_items);
+        let surface = Surface::new(crate_data, &renderer);
+        let mut modules = Vec::new();
+        for module in &surface.modules {
+            let mut stats = ModuleStats {
+                path: module.path.clone(),
+                ..ModuleStats::default()
+            };
+            for &item in &module.items {
+                let Some(kind) = ItemKind::of(item) else {
+                    continue;
+                };
+                *stats.items.entry(kind.name().to_string()).or_default() += 1;
+                if kind == ItemKind::Import {
+                    continue;
+                }
+                stats.count(item);
+                for member in surface.members(item) {
+                    if matches!(member.inner, ItemEnum::Function(_)) {
+                        stats.methods += 1;
+                        stats.count(member);
+                    }
+                }
+            }
+            modules.push(stats);
+        }
+        ApiStats::from_modules(modules)
+    }
+
+    /// Keeps only the module at `path` and the modules below it. Returns None if there is no
+    /// such module.
+    pub fn select(self, path: &str) -> Option<Self> {
+        let prefix = format!("{}::", path);
+        let modules: Vec<ModuleStats> = self
+            .modules
+            .into_iter()
+            .filter(|m| m.path == path || m.path.starts_with(&prefix))
+            .collect();
+        (!modules.is_empty()).then(|| ApiStats::from_modules(modules))
+    }
+
+    fn from_modules(modules: Vec<ModuleStats>) -> Self {
+        let mut total = ModuleStats {
+            path: "total".to_string(),
+            ..ModuleStats::default()
+        };
+        for module in &modules {
+            total.add(module);
+        }
+        ApiStats { modules, total }
+    }
+}
+
+/// Renders the metrics as a table with a row per module, and averages per signature.
+impl fmt::Display for ApiStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut header = vec!["module".to_string()];
+        header.extend(ItemKind::ALL.iter().map(|k| k.name().to_string()));
+        header.extend(
+            [
+                "methods",
+                "undocumented",
+                "params/sig",
+                "bounds/sig",
+                "unsafe",
+                "deprecated",
+            ]
+            .map(String::from),
+        );
+
+        let per_signature = |count: usize, signatures: usize| {
+            if signatures == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.2}", count as f64 / signatures as f64)
+            }
+        };
+        let mut rows = vec![header];
+        for module in self.modules.iter().chain([&self.total]) {
+            let mut row = vec![module.path.clone()];
+            row.extend(
+                ItemKind::ALL
+                    .iter()
+                    .map(|k| module.items.get(k.name()).copied().unwrap_or(0).to_string()),
+            );
+            row.extend([
+                module.methods.to_string(),
+                module.undocumented.to_string(),
+                per_signature(module.generic_params, module.signatures),
+                per_signature(module.generic_bounds, module.signatures),
+                module.unsafe_functions.to_string(),
+                module.deprecated.to_string(),
+            ]);
+            rows.push(row);
+        }
+
+        write_table(f, &rows)
+    }
+}
+
+/// Writes rows as a table with aligned columns: the first left-aligned, the others right-aligned.
+pub(crate) fn write_table(f: &mut fmt::Formatter<'_>, rows: &[Vec<String>]) -> fmt::Result {
+    let columns = rows.first().map_or(0, |row| row.len());
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| rows.iter().map(|row| row[i].len()).max().unwrap_or(0))
+        .collect();
+    for row in rows {
+        let mut line = format!("{:<width$}", row[0], width = widths[0]);
+        for (cell, width) in row.iter().zip(&widths).skip(1) {
+            line.push_str(&format!("  {:>width$}", cell, width = width));
+        }
+        writeln!(f, "{}", line)?;
+    }
+    Ok(())
+}
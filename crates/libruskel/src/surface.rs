@@ -0,0 +1,334 @@
+//! The public surface of a crate: its public modules and the items reachable through them, and
+//! the public paths users name those items by.
+
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
+
+use crate::crateutils::inherent_items;
+use crate::filter::ItemKind;
+use crate::render::Renderer;
+
+/// Decides which items of a module are on the surface and what they're exported as, following
+/// the renderer's visibility rules.
+struct Exports<'a> {
+    crate_data: &'a Crate,
+    renderer: &'a Renderer,
+    /// Leaves out private items, whether or not the renderer shows them.
+    public_only: bool,
+}
+
+/// An item a module makes available under a name.
+struct Export<'a> {
+    name: String,
+    id: &'a Id,
+    /// The item, or the re-export itself if it can't be resolved to an item in the crate.
+    item: &'a Item,
+    /// The re-export the item is available through, if it isn't declared in the module. Items
+    /// brought in by a glob re-export are available through the glob.
+    import: Option<(&'a Id, &'a Item)>,
+}
+
+impl<'a> Exports<'a> {
+    fn included(&self, item: &Item) -> bool {
+        let visible = if self.public_only {
+            matches!(item.visibility, Visibility::Public)
+        } else {
+            self.renderer.is_visible(item)
+        };
+        visible && !self.is_hidden(item)
+    }
+
+    fn is_hidden(&self, item: &Item) -> bool {
+        self.renderer.is_hidden(self.crate_data, item)
+    }
+
+    fn children(&self, module: &'a Item) -> Vec<(&'a Id, &'a Item)> {
+        let ItemEnum::Module(module) = &module.inner else {
+            return Vec::new();
+        };
+        module
+            .items
+            .iter()
+            .filter_map(|id| self.crate_data.index.get_key_value(id))
+            .filter(|(_, item)| self.included(item))
+            .collect()
+    }
+
+    /// Returns the items of a module with the names they're available under, resolving
+    /// re-exports to the items they refer to and expanding glob re-exports of modules.
+    fn of(&self, module: &'a Item) -> Vec<Export<'a>> {
+        let mut output = Vec::new();
+        self.collect(module, None, &mut HashSet::new(), &mut output);
+        output
+    }
+
+    /// Adds the exports of a module to `output`. `glob` is the glob re-export the module's items
+    /// are brought in by, if any, and `globs` holds the modules already expanded, so cyclic globs
+    /// terminate.
+    fn collect(
+        &self,
+        module: &'a Item,
+        glob: Option<(&'a Id, &'a Item)>,
+        globs: &mut HashSet<&'a Id>,
+        output: &mut Vec<Export<'a>>,
+    ) {
+        for (id, item) in self.children(module) {
+            let ItemEnum::Import(import) = &item.inner else {
+                if let Some(name) = &item.name {
+                    output.push(Export {
+                        name: name.clone(),
+                        id,
+                        item,
+                        import: glob,
+                    });
+                }
+                continue;
+            };
+            let via = glob.or(Some((id, item)));
+            let unresolved = Export {
+                name: import.name.clone(),
+                id,
+                item,
+                import: via,
+            };
+            match import
+                .id
+                .as_ref()
+                .and_then(|id| self.crate_data.index.get_key_value(id))
+            {
+                Some((target_id, target)) if import.glob => {
+                    if matches!(target.inner, ItemEnum::Module(_)) {
+                        if globs.insert(target_id) {
+                            self.collect(target, via, globs, output);
+                        }
+                    } else {
+                        output.push(unresolved);
+                    }
+                }
+                Some((target_id, target)) => {
+                    if !self.is_hidden(target) {
+                        output.push(Export {
+                            name: import.name.clone(),
+                            id: target_id,
+                            item: target,
+                            import: via,
+                        });
+                    }
+                }
+                None => output.push(unresolved),
+            }
+        }
+    }
+}
+
+/// A module and the items it makes public.
+pub struct SurfaceModule<'a> {
+    pub path: String,
+    pub module: &'a Item,
+    /// The module's items. Re-exports of items that aren't reachable otherwise are replaced by
+    /// the item they refer to, and glob re-exports by the items of the module they refer to.
+    pub items: Vec<&'a Item>,
+}
+
+pub struct Surface<'a> {
+    exports: Exports<'a>,
+    /// Items reachable through modules, without following re-exports.
+    reachable: HashSet<&'a Id>,
+    /// Re-exports and re-exported items already listed, so each is listed once.
+    listed: HashSet<&'a Id>,
+    /// Modules in the order they're declared, starting with the crate root.
+    pub modules: Vec<SurfaceModule<'a>>,
+}
+
+impl<'a> Surface<'a> {
+    /// Finds the modules of a crate and the items in them that the renderer shows: public ones,
+    /// or all if it renders private items, and not those it hides. Items declared in a module are
+    /// listed there, and items only available through re-exports where they're first re-exported.
+    pub fn new(crate_data: &'a Crate, renderer: &'a Renderer) -> Self {
+        let mut surface = Surface {
+            exports: Exports {
+                crate_data,
+                renderer,
+                public_only: false,
+            },
+            reachable: HashSet::new(),
+            listed: HashSet::new(),
+            modules: Vec::new(),
+        };
+        if let Some(root) = crate_data.index.get(&crate_data.root) {
+            surface.find_reachable(root);
+            let name = root.name.clone().unwrap_or_default();
+            surface.walk_module(root, name);
+        }
+        surface
+    }
+
+    /// Is the item rendered, going by its visibility and attributes?
+    pub fn included(&self, item: &Item) -> bool {
+        self.exports.included(item)
+    }
+
+    /// Is the item left out by the renderer whatever its visibility, such as a `#[doc(hidden)]`
+    /// item or enum variant?
+    pub fn is_hidden(&self, item: &Item) -> bool {
+        self.exports.is_hidden(item)
+    }
+
+    /// Returns the rendered items of a trait, or of a type's inherent impls.
+    pub fn members(&self, item: &'a Item) -> Vec<&'a Item> {
+        let crate_data = self.exports.crate_data;
+        match &item.inner {
+            ItemEnum::Trait(trait_) => trait_
+                .items
+                .iter()
+                .filter_map(|id| crate_data.index.get(id))
+                .filter(|member| !self.is_hidden(member))
+                .collect(),
+            _ => inherent_items(crate_data, item)
+                .filter(|(impl_item, member)| !self.is_hidden(impl_item) && self.included(member))
+                .map(|(_, member)| member)
+                .collect(),
+        }
+    }
+
+    fn find_reachable(&mut self, module: &'a Item) {
+        for (id, item) in self.exports.children(module) {
+            if !matches!(item.inner, ItemEnum::Import(_)) && self.reachable.insert(id) {
+                self.find_reachable(item);
+            }
+        }
+    }
+
+    fn walk_module(&mut self, module: &'a Item, path: String) {
+        let index = self.modules.len();
+        self.modules.push(SurfaceModule {
+            path: path.clone(),
+            module,
+            items: Vec::new(),
+        });
+        for export in self.exports.of(module) {
+            let (id, item) = match export.import {
+                None => (export.id, export.item),
+                // Items reachable elsewhere are listed there, so here only the re-export is
+                Some(import) if self.reachable.contains(export.id) => import,
+                Some(_) => (export.id, export.item),
+            };
+            if export.import.is_some() && !self.listed.insert(id) {
+                continue;
+            }
+            self.modules[index].items.push(item);
+            if matches!(item.inner, ItemEnum::Module(_)) {
+                let name = item.name.as_deref().unwrap_or_default();
+                self.walk_module(item, format!("{}::{}", path, name));
+            }
+        }
+    }
+}
+
+/// A module at its shortest public path, holding the items whose shortest path is in it.
+pub struct PublicModule<'a> {
+    pub path: String,
+    pub module: &'a Item,
+    /// The other paths the module is public at.
+    pub aliases: Vec<String>,
+    pub items: Vec<PublicItem<'a>>,
+    /// Indexes of the modules whose shortest path is directly below this one.
+    pub children: Vec<usize>,
+}
+
+impl PublicModule<'_> {
+    /// The name the module is public under, which may differ from its own if it's re-exported.
+    pub fn name(&self) -> &str {
+        self.path.rsplit("::").next().unwrap_or(&self.path)
+    }
+}
+
+/// An item at its shortest public path. Re-exports of items from other crates that aren't
+/// documented in this one are kept as the re-export itself.
+pub struct PublicItem<'a> {
+    /// The name the item is public under at its shortest path, which differs from its own name
+    /// if it's re-exported under another.
+    pub name: String,
+    pub item: &'a Item,
+    /// The other paths the item is public at.
+    pub aliases: Vec<String>,
+}
+
+/// Every public module and item of a crate, each listed once at its shortest public path.
+pub struct PublicPaths<'a> {
+    /// Modules in order of path length, starting with the crate root.
+    pub modules: Vec<PublicModule<'a>>,
+}
+
+impl<'a> PublicPaths<'a> {
+    /// Finds the public paths of everything in the crate, leaving out the items the renderer
+    /// hides. Private items are left out even if the renderer shows them. Paths of the same
+    /// length are ordered as declared.
+    pub fn new(crate_data: &'a Crate, renderer: &'a Renderer) -> Self {
+        let exports = Exports {
+            crate_data,
+            renderer,
+            public_only: true,
+        };
+        let mut paths = PublicPaths {
+            modules: Vec::new(),
+        };
+        let Some(root) = crate_data.index.get(&crate_data.root) else {
+            return paths;
+        };
+        paths.modules.push(PublicModule {
+            path: root.name.clone().unwrap_or_default(),
+            module: root,
+            aliases: Vec::new(),
+            items: Vec::new(),
+            children: Vec::new(),
+        });
+
+        // Modules are added as they're found, so walking them in order is a breadth-first search
+        // and the first path found for anything is a shortest one.
+        let mut modules: HashMap<&Id, usize> = HashMap::from([(&crate_data.root, 0)]);
+        let mut items: HashMap<&Id, (usize, usize)> = HashMap::new();
+        let mut index = 0;
+        while index < paths.modules.len() {
+            for export in exports.of(paths.modules[index].module) {
+                if ItemKind::of(export.item).is_none() {
+                    continue;
+                }
+                let path = format!("{}::{}", paths.modules[index].path, export.name);
+                if matches!(export.item.inner, ItemEnum::Module(_)) {
+                    if let Some(&existing) = modules.get(export.id) {
+                        paths.modules[existing].aliases.push(path);
+                        continue;
+                    }
+                    let child = paths.modules.len();
+                    modules.insert(export.id, child);
+                    paths.modules[index].children.push(child);
+                    paths.modules.push(PublicModule {
+                        path,
+                        module: export.item,
+                        aliases: Vec::new(),
+                        items: Vec::new(),
+                        children: Vec::new(),
+                    });
+                } else if let Some(&(module, entry)) = items.get(export.id) {
+                    paths.modules[module].items[entry].aliases.push(path);
+                } else {
+                    items.insert(export.id, (index, paths.modules[index].items.len()));
+                    paths.modules[index].items.push(PublicItem {
+                        name: export.name,
+                        item: export.item,
+                        aliases: Vec::new(),
+                    });
+                }
+            }
+            index += 1;
+        }
+        paths
+    }
+
+    /// Returns the index of the module whose shortest public path is `path`.
+    pub fn find_module(&self, path: &str) -> Option<usize> {
+        self.modules.iter().position(|m| m.path == path)
+    }
+}
@@ -0,0 +1,214 @@
+//! An index of the functions that produce and consume each type, built from function signatures.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Type, Visibility};
+
+use crate::crateutils::{
+    render_function_args, render_generics, render_path, render_return_type, type_impls,
+};
+use crate::signature::type_paths;
+
+/// A function or method found in the usage index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionUse {
+    /// The path of the function, e.g. `mycrate::Builder::build`. Trait impl methods are shown as
+    /// `<mycrate::Config as Default>::default`.
+    pub path: String,
+    /// The function's signature on one line, e.g. `fn mycrate::Builder::build(self) -> Config`.
+    pub signature: String,
+}
+
+/// The functions that produce or consume a type, as returned by [`UsageIndex::uses`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeUses {
+    /// The name of the type.
+    pub name: String,
+    /// Functions and associated functions without a `self` parameter that return the type.
+    pub constructors: Vec<FunctionUse>,
+    /// Methods that return the type.
+    pub methods: Vec<FunctionUse>,
+    /// Functions and methods with a parameter other than `self` that takes the type.
+    pub consumers: Vec<FunctionUse>,
+}
+
+impl fmt::Display for TypeUses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            ("Constructors", &self.constructors),
+            (&format!("Methods returning {}", self.name), &self.methods),
+            ("Consumers", &self.consumers),
+        ];
+        if sections.iter().all(|(_, uses)| uses.is_empty()) {
+            return writeln!(f, "no functions produce or consume {}", self.name);
+        }
+        for (title, uses) in sections {
+            if uses.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for function in uses {
+                writeln!(f, "    {}", function.signature)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maps each type to the functions that return it and the functions that take it, looking at the
+/// types named anywhere in parameter and return types, so `Option<Foo>` and `&[Foo]` count.
+pub struct UsageIndex {
+    functions: Vec<IndexedFunction>,
+    produced_by: HashMap<Id, Vec<usize>>,
+    consumed_by: HashMap<Id, Vec<usize>>,
+}
+
+struct IndexedFunction {
+    function: FunctionUse,
+    is_method: bool,
+}
+
+impl UsageIndex {
+    /// Indexes the free functions, inherent and trait impl methods, and trait methods of a crate.
+    /// Private items are only indexed if `private_items` is set.
+    pub fn new(crate_data: &Crate, private_items: bool) -> Self {
+        let mut index = UsageIndex {
+            functions: Vec::new(),
+            produced_by: HashMap::new(),
+            consumed_by: HashMap::new(),
+        };
+        let visible = |item: &Item| private_items || matches!(item.visibility, Visibility::Public);
+
+        for (id, summary) in &crate_data.paths {
+            if summary.crate_id != 0 {
+                continue;
+            }
+            let Some(item) = crate_data.index.get(id).filter(|item| visible(item)) else {
+                continue;
+            };
+            let path = summary.path.join("::");
+            match &item.inner {
+                ItemEnum::Function(_) => index.add(path, item, None),
+                ItemEnum::Trait(trait_) => {
+                    for member in trait_
+                        .items
+                        .iter()
+                        .filter_map(|id| crate_data.index.get(id))
+                    {
+                        if let Some(name) = &member.name {
+                            index.add(format!("{}::{}", path, name), member, None);
+                        }
+                    }
+                }
+                ItemEnum::Struct(_) | ItemEnum::Enum(_) | ItemEnum::Union(_) => {
+                    for (impl_item, impl_) in type_impls(crate_data, item) {
+                        // Impls are also listed on the types they mention, but are only indexed
+                        // under the type they're for. Derived impls would be noise.
+                        let is_for_type =
+                            matches!(&impl_.for_, Type::ResolvedPath(p) if &p.id == id);
+                        if !is_for_type
+                            || impl_.synthetic
+                            || impl_.blanket_impl.is_some()
+                            || is_derived(impl_item)
+                        {
+                            continue;
+                        }
+                        let prefix = match &impl_.trait_ {
+                            Some(trait_) => format!("<{} as {}>", path, render_path(trait_)),
+                            None => path.clone(),
+                        };
+                        for member in impl_.items.iter().filter_map(|id| crate_data.index.get(id)) {
+                            if impl_.trait_.is_none() && !visible(member) {
+                                continue;
+                            }
+                            if let Some(name) = &member.name {
+                                index.add(format!("{}::{}", prefix, name), member, Some(id));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        index
+    }
+
+    /// Indexes a function, if the item is one. `self_type` is the type `Self` refers to.
+    fn add(&mut self, path: String, item: &Item, self_type: Option<&Id>) {
+        let ItemEnum::Function(function) = &item.inner else {
+            return;
+        };
+        let decl = &function.decl;
+        let is_method = decl.inputs.first().is_some_and(|(name, _)| name == "self");
+        let index = self.functions.len();
+
+        let mut produced = Vec::new();
+        if let Some(output) = &decl.output {
+            produced = type_paths(output, self_type);
+        }
+        let mut consumed: Vec<Id> = Vec::new();
+        for (name, ty) in &decl.inputs {
+            if name == "self" {
+                continue;
+            }
+            for id in type_paths(ty, self_type) {
+                if !consumed.contains(&id) {
+                    consumed.push(id);
+                }
+            }
+        }
+        if produced.is_empty() && consumed.is_empty() {
+            return;
+        }
+        for id in produced {
+            self.produced_by.entry(id).or_default().push(index);
+        }
+        for id in consumed {
+            self.consumed_by.entry(id).or_default().push(index);
+        }
+
+        let return_type = render_return_type(decl);
+        let signature = format!(
+            "fn {}{}({}){}{}",
+            path,
+            render_generics(&function.generics),
+            render_function_args(decl),
+            if return_type.is_empty() { "" } else { " " },
+            return_type
+        );
+        self.functions.push(IndexedFunction {
+            function: FunctionUse { path, signature },
+            is_method,
+        });
+    }
+
+    /// Returns the functions that produce or consume the type with the given id, sorted by path.
+    pub fn uses(&self, id: &Id, name: &str) -> TypeUses {
+        let collect = |map: &HashMap<Id, Vec<usize>>, methods: Option<bool>| {
+            let mut uses: Vec<FunctionUse> = map
+                .get(id)
+                .into_iter()
+                .flatten()
+                .map(|&i| &self.functions[i])
+                .filter(|f| methods.is_none_or(|m| f.is_method == m))
+                .map(|f| f.function.clone())
+                .collect();
+            uses.sort_by(|a, b| a.path.cmp(&b.path));
+            uses
+        };
+        TypeUses {
+            name: name.to_string(),
+            constructors: collect(&self.produced_by, Some(false)),
+            methods: collect(&self.produced_by, Some(true)),
+            consumers: collect(&self.consumed_by, None),
+        }
+    }
+}
+
+/// Is the impl generated by a derive macro?
+fn is_derived(item: &Item) -> bool {
+    item.attrs
+        .iter()
+        .any(|attr| attr.contains("automatically_derived"))
+}
@@ -1,5 +1,5 @@
 mod utils;
-use libruskel::Renderer;
+use libruskel::{ItemKind, Renderer};
 use utils::*;
 
 gen_tests! {
@@ -260,6 +260,121 @@ gen_tests! {
     }
 }
 
+gen_tests! {
+    filter_patterns, {
+        rt_custom {
+            glob_component: {
+                renderer: Renderer::default().with_filter("dummy_crate::de::*Deserialize*"),
+                input: r#"
+                    pub mod de {
+                        pub trait Deserialize {}
+                        pub trait DeserializeOwned {}
+                        pub trait Visitor {}
+                    }
+                "#,
+                output: r#"
+                    pub mod de {
+                        pub trait Deserialize {}
+                        pub trait DeserializeOwned {}
+                    }
+                "#
+            }
+        }
+        rt_custom {
+            any_depth: {
+                renderer: Renderer::default().with_filter("dummy_crate::**::spawn"),
+                input: r#"
+                    pub fn spawn() {}
+                    pub mod sync {
+                        pub fn lock() {}
+                    }
+                    pub mod task {
+                        //! Task docs
+                        pub fn spawn() {}
+                        pub fn yield_now() {}
+                        pub mod blocking {
+                            pub fn spawn() {}
+                            pub fn other() {}
+                        }
+                    }
+                "#,
+                output: r#"
+                    pub mod task {
+                        pub mod blocking {
+                            pub fn spawn() {}
+                        }
+                        pub fn spawn() {}
+                    }
+                    pub fn spawn() {}
+                "#
+            }
+        }
+        rt_custom {
+            regex_component: {
+                renderer: Renderer::default().with_filter("dummy_crate::/(de|ser)/::Error"),
+                input: r#"
+                    pub mod de {
+                        pub struct Error;
+                        pub struct Other;
+                    }
+                    pub mod ser {
+                        pub struct Error;
+                    }
+                    pub mod des {
+                        pub struct Error;
+                    }
+                "#,
+                output: r#"
+                    pub mod de {
+                        pub struct Error;
+                    }
+                    pub mod ser {
+                        pub struct Error;
+                    }
+                "#
+            }
+        }
+        rt_custom {
+            multiple_filters: {
+                renderer: Renderer::default().with_filter("dummy_crate::a::one, dummy_crate::b"),
+                input: r#"
+                    pub mod a {
+                        pub fn one() {}
+                        pub fn two() {}
+                    }
+                    pub mod b {
+                        //! Module B docs
+                        pub fn three() {}
+                    }
+                    pub mod c {
+                        pub fn four() {}
+                    }
+                "#,
+                output: r#"
+                    pub mod a {
+                        pub fn one() {}
+                    }
+                    pub mod b {
+                        //! Module B docs
+                        pub fn three() {}
+                    }
+                "#
+            }
+        }
+        rt_err {
+            glob_not_matched: {
+                renderer: Renderer::default().with_filter("dummy_crate::**::missing*"),
+                input: r#"
+                    pub mod my_module {
+                        pub fn my_function() {}
+                    }
+                "#,
+                error: "Filter 'dummy_crate::**::missing*' did not match any items"
+            }
+        }
+    }
+}
+
 gen_tests! {
     filter_error, {
         rt_custom {
@@ -331,3 +446,288 @@ gen_tests! {
 
     }
 }
+
+gen_tests! {
+    exclude, {
+        rt_custom {
+            exclude_module_by_name: {
+                renderer: Renderer::default().with_exclude("__private").with_exclude("tests"),
+                input: r#"
+                    pub mod __private {
+                        pub fn support() {}
+                    }
+                    pub mod api {
+                        pub fn call() {}
+                        pub mod tests {
+                            pub fn check() {}
+                        }
+                    }
+                "#,
+                output: r#"
+                    pub mod api {
+                        pub fn call() {}
+                    }
+                "#
+            }
+        }
+        rt_custom {
+            exclude_full_path: {
+                renderer: Renderer::default().with_exclude("dummy_crate::a::internal"),
+                input: r#"
+                    pub mod a {
+                        pub mod internal {
+                            pub fn f() {}
+                        }
+                    }
+                    pub mod internal {
+                        pub fn g() {}
+                    }
+                "#,
+                output: r#"
+                    pub mod a {}
+                    pub mod internal {
+                        pub fn g() {}
+                    }
+                "#
+            }
+        }
+        rt_custom {
+            exclude_kind: {
+                renderer: Renderer::default().with_exclude("macro:*"),
+                input: r#"
+                    #[macro_export]
+                    macro_rules! my_macro {
+                        () => {};
+                    }
+                    pub fn my_macro() {}
+                "#,
+                output: r#"
+                    pub fn my_macro() {}
+                "#
+            }
+        }
+        rt_custom {
+            exclude_reexport: {
+                renderer: Renderer::default().with_exclude("Hidden*"),
+                input: r#"
+                    mod private {
+                        pub struct Visible;
+                        pub struct HiddenThing;
+                    }
+                    pub use private::{HiddenThing, Visible};
+                "#,
+                output: r#"
+                    pub struct Visible;
+                "#
+            }
+        }
+        rt_custom {
+            exclude_with_filter: {
+                renderer: Renderer::default()
+                    .with_filter("dummy_crate::api")
+                    .with_exclude("dummy_crate::api::old_*"),
+                input: r#"
+                    pub mod api {
+                        pub fn new_call() {}
+                        pub fn old_call() {}
+                    }
+                "#,
+                output: r#"
+                    pub mod api {
+                        pub fn new_call() {}
+                    }
+                "#
+            }
+        }
+        rt_custom {
+            exclude_colon_pattern: {
+                renderer: Renderer::default().with_exclude("/a:b|f/"),
+                input: r#"
+                    pub fn f() {}
+                    pub fn g() {}
+                "#,
+                output: r#"
+                    pub fn g() {}
+                "#
+            }
+        }
+        rt_err {
+            exclude_unknown_kind: {
+                renderer: Renderer::default().with_exclude("widget:*"),
+                input: r#"
+                    pub fn f() {}
+                "#,
+                error: "Invalid filter: unknown item kind 'widget', expected one of: mod, struct, enum, trait, fn, const, type, macro, use"
+            }
+        }
+    }
+}
+
+gen_tests! {
+    kinds, {
+        rt_custom {
+            functions_only: {
+                renderer: Renderer::default().with_kinds(&[ItemKind::Function]),
+                input: r#"
+                    pub fn entry() {}
+                    pub struct Config;
+                    impl Config {
+                        pub fn new() -> Self { Config }
+                    }
+                    pub mod util {
+                        pub fn helper() {}
+                        pub const LIMIT: u32 = 1;
+                    }
+                    pub mod types {
+                        pub struct Other;
+                    }
+                "#,
+                output: r#"
+                    pub mod util {
+                        pub fn helper() {}
+                    }
+                    pub fn entry() {}
+                "#
+            }
+        }
+        rt_custom {
+            traits_and_structs: {
+                renderer: Renderer::default().with_kinds(&[ItemKind::Trait, ItemKind::Struct]),
+                input: r#"
+                    pub trait Service {
+                        fn call(&self);
+                    }
+                    pub struct Handler;
+                    impl Service for Handler {
+                        fn call(&self) {}
+                    }
+                    pub enum Mode { A, B }
+                    pub type Alias = Handler;
+                "#,
+                output: r#"
+                    pub trait Service {
+                        fn call(&self);
+                    }
+                    pub struct Handler;
+                    impl Service for Handler {
+                        fn call(&self) {}
+                    }
+                "#
+            }
+        }
+        rt_custom {
+            reexports_by_target_kind: {
+                renderer: Renderer::default().with_kinds(&[ItemKind::Struct]),
+                input: r#"
+                    mod private {
+                        pub struct Inlined;
+                        pub fn dropped() {}
+                    }
+                    pub use private::{dropped, Inlined};
+                    pub use std::rc::Rc;
+                "#,
+                output: r#"
+                    pub struct Inlined;
+                "#
+            }
+        }
+        rt_custom {
+            imports_kind: {
+                renderer: Renderer::default().with_kinds(&[ItemKind::Import]),
+                input: r#"
+                    pub use std::rc::Rc;
+                    pub fn f() {}
+                "#,
+                output: r#"
+                    pub use std::rc::Rc;
+                "#
+            }
+        }
+        rt_custom {
+            kinds_with_filter: {
+                renderer: Renderer::default()
+                    .with_filter("dummy_crate::api")
+                    .with_kinds(&[ItemKind::Function]),
+                input: r#"
+                    pub mod api {
+                        //! API docs
+                        pub fn call() {}
+                        pub struct Request;
+                    }
+                    pub fn other() {}
+                "#,
+                output: r#"
+                    pub mod api {
+                        //! API docs
+                        pub fn call() {}
+                    }
+                "#
+            }
+        }
+    }
+}
+
+#[test]
+fn test_filter_hidden() {
+    let source = r#"
+        pub mod api {
+            pub fn call() {}
+            #[doc(hidden)]
+            pub fn __call_impl() {}
+        }
+
+        #[doc(hidden)]
+        pub mod __private {
+            pub fn support() {}
+        }
+    "#;
+
+    rt_hidden(
+        Renderer::default().with_filter("dummy_crate::api"),
+        source,
+        r#"
+            pub mod api {
+                pub fn call() {}
+            }
+        "#,
+    );
+    rt_hidden(
+        Renderer::default()
+            .with_filter("dummy_crate::__private")
+            .with_hidden_items(true),
+        source,
+        r#"
+            pub mod __private {
+                pub fn support() {}
+            }
+        "#,
+    );
+    rt_hidden(
+        Renderer::default()
+            .with_hidden_items(true)
+            .with_exclude("__*"),
+        source,
+        r#"
+            pub mod api {
+                pub fn call() {}
+            }
+        "#,
+    );
+}
+
+gen_tests! {
+    hidden, {
+        rt_err {
+            filter_hidden_not_matched: {
+                renderer: Renderer::default().with_filter("dummy_crate::__private"),
+                input: r#"
+                    #[doc(hidden)]
+                    pub mod __private {
+                        pub fn support() {}
+                    }
+                "#,
+                error: "Filter 'dummy_crate::__private' did not match any items"
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 mod utils;
+use libruskel::{CrateTarget, Deprecated, ModuleStats, Order, Renderer, Ruskel, SourceLocations};
+use std::fs;
 use utils::*;
 
 #[test]
@@ -250,3 +252,1231 @@ fn test_render_proc_macro_with_attributes() {
 
     rt_procmacro(source, expected_output);
 }
+
+#[test]
+fn test_render_doc_hidden() {
+    let source = r#"
+        #[doc(hidden)]
+        pub mod __private {
+            pub fn support() {}
+        }
+
+        mod inner {
+            #[doc(hidden)]
+            pub struct Internal;
+            pub struct Exposed;
+        }
+
+        pub use inner::{Exposed, Internal};
+
+        pub struct Visible {
+            pub field: u8,
+            #[doc(hidden)]
+            pub hidden_field: u8,
+        }
+
+        pub enum Mode {
+            On,
+            #[doc(hidden)]
+            __NonExhaustive,
+        }
+
+        pub trait Api {
+            fn call(&self);
+            #[doc(hidden)]
+            fn __internal(&self) {}
+        }
+
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! __helper {
+            () => {};
+        }
+    "#;
+
+    let expected_output = r#"
+            pub struct Exposed;
+
+            pub struct Visible {
+                pub field: u8,
+            }
+
+            pub enum Mode {
+                On,
+            }
+
+            pub trait Api {
+                fn call(&self);
+            }
+        "#;
+
+    // Stripped by rustdoc
+    rt(source, expected_output);
+    // Present in the crate data, but hidden by the renderer
+    rt_hidden(Renderer::default(), source, expected_output);
+
+    rt_hidden(
+        Renderer::default().with_hidden_items(true),
+        source,
+        r#"
+            pub mod __private {
+                pub fn support() {}
+            }
+
+            pub struct Exposed;
+
+            pub struct Internal;
+
+            pub struct Visible {
+                pub field: u8,
+                pub hidden_field: u8,
+            }
+
+            pub enum Mode {
+                On,
+                __NonExhaustive,
+            }
+
+            pub trait Api {
+                fn call(&self);
+                fn __internal(&self) {}
+            }
+
+            #[macro_export]
+            macro_rules! __helper {
+                () => { ... };
+            }
+        "#,
+    );
+}
+
+#[test]
+fn test_render_deprecated() {
+    rt_idemp(
+        r#"
+            #[deprecated(since = "0.9")]
+            pub mod old_mod {}
+
+            #[deprecated(since = "1.2.0", note = "use `new_fn` instead")]
+            pub fn old_fn() {}
+
+            #[deprecated(note = "no \"since\" here")]
+            pub struct OldStruct {
+                #[deprecated]
+                pub field: u8,
+            }
+
+            pub struct Current;
+
+            impl Current {
+                #[deprecated]
+                pub fn old_method(&self) {}
+            }
+        "#,
+    );
+}
+
+#[test]
+fn test_render_deprecated_modes() {
+    let source = r#"
+        #[deprecated]
+        pub fn old_fn() {}
+
+        pub fn new_fn() {}
+
+        #[deprecated]
+        pub struct OldStruct;
+
+        pub struct NewStruct;
+
+        impl NewStruct {
+            #[deprecated]
+            pub fn old_method(&self) {}
+            pub fn new_method(&self) {}
+        }
+
+        mod private {
+            #[deprecated]
+            pub struct Reexported;
+        }
+
+        pub use private::Reexported;
+    "#;
+
+    render(
+        Renderer::default().with_deprecated(Deprecated::Hide),
+        source,
+        r#"
+            pub fn new_fn() {}
+
+            pub struct NewStruct;
+
+            impl NewStruct {
+                pub fn new_method(&self) {}
+            }
+        "#,
+        false,
+    );
+
+    render(
+        Renderer::default().with_deprecated(Deprecated::Last),
+        source,
+        r#"
+            pub fn new_fn() {}
+
+            pub struct NewStruct;
+
+            impl NewStruct {
+                #[deprecated]
+                pub fn old_method(&self) {}
+                pub fn new_method(&self) {}
+            }
+
+            #[deprecated]
+            pub fn old_fn() {}
+
+            #[deprecated]
+            pub struct OldStruct;
+
+            #[deprecated]
+            pub struct Reexported;
+        "#,
+        false,
+    );
+}
+
+#[test]
+fn test_render_order() {
+    let source = r#"
+        pub fn zeta() {}
+
+        pub struct Widget;
+
+        impl Clone for Widget {
+            fn clone(&self) -> Self { Widget }
+        }
+
+        impl Widget {
+            pub fn run(&self) {}
+            pub fn new() -> Self { Widget }
+        }
+
+        pub trait Action {
+            fn perform(&self);
+            type Output;
+        }
+
+        pub const MAX: u32 = 10;
+
+        pub fn alpha() {}
+    "#;
+
+    render(
+        Renderer::default().with_order(Order::Source),
+        source,
+        r#"
+            pub fn zeta() {}
+
+            pub struct Widget;
+
+            impl Clone for Widget {
+                fn clone(&self) -> Self {}
+            }
+
+            impl Widget {
+                pub fn run(&self) {}
+                    pub fn new() -> Self {}
+            }
+
+            pub trait Action {
+                fn perform(&self);
+                type Output;
+            }
+
+            pub const MAX: u32 = 10;
+
+            pub fn alpha() {}
+        "#,
+        false,
+    );
+
+    render(
+        Renderer::default().with_order(Order::Kind),
+        source,
+        r#"
+            pub struct Widget;
+
+            impl Widget {
+                    pub fn run(&self) {}
+                pub fn new() -> Self {}
+            }
+
+            impl Clone for Widget {
+                fn clone(&self) -> Self {}
+            }
+
+            pub trait Action {
+                type Output;
+                fn perform(&self);
+            }
+
+            pub const MAX: u32 = 10;
+
+            pub fn zeta() {}
+
+            pub fn alpha() {}
+        "#,
+        false,
+    );
+
+    render(
+        Renderer::default().with_order(Order::Alphabetical),
+        source,
+        r#"
+            pub struct Widget;
+
+            impl Widget {
+                    pub fn new() -> Self {}
+                pub fn run(&self) {}
+            }
+
+            impl Clone for Widget {
+                fn clone(&self) -> Self {}
+            }
+
+            pub trait Action {
+                type Output;
+                fn perform(&self);
+            }
+
+            pub const MAX: u32 = 10;
+
+            pub fn alpha() {}
+
+            pub fn zeta() {}
+        "#,
+        false,
+    );
+}
+
+#[test]
+fn test_render_source_locations() {
+    let source = r#"
+pub mod inner {
+    pub fn nested() {}
+}
+
+/// A struct
+pub struct Foo {
+    pub field: u32,
+}
+
+impl Foo {
+    pub fn method(&self) {}
+}
+
+pub fn free() {}
+"#;
+
+    render(
+        Renderer::default().with_source_locations(SourceLocations::Relative),
+        source,
+        r#"
+            // src/lib.rs:2
+            pub mod inner {
+                // src/lib.rs:3
+                pub fn nested() {}
+            }
+
+            // src/lib.rs:7
+            /// A struct
+            pub struct Foo {
+                // src/lib.rs:8
+                pub field: u32,
+            }
+
+            // src/lib.rs:11
+            impl Foo {
+                // src/lib.rs:12
+                pub fn method(&self) {}
+            }
+
+            // src/lib.rs:15
+            pub fn free() {}
+        "#,
+        false,
+    );
+}
+
+#[test]
+fn test_render_source_locations_absolute() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"locs\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(root.join("src/lib.rs"), "pub fn free() {}\n").unwrap();
+
+    let rendered = Ruskel::new(root.to_str().unwrap())
+        .with_offline(true)
+        .render_with(Renderer::default().with_source_locations(SourceLocations::Absolute))
+        .unwrap();
+    let lib = root.canonicalize().unwrap().join("src/lib.rs");
+    assert!(
+        rendered.contains(&format!("// {}:1\n", lib.display())),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_render_expand() {
+    let source = r#"
+pub mod parser {
+    pub struct Parser {
+        pos: usize,
+    }
+
+    impl Parser {
+        pub fn new() -> Self {
+            Parser { pos: 0 }
+        }
+
+        /// Advances the parser.
+        pub fn advance(&mut self) -> usize {
+            // Braces in strings and comments: "{" '}'
+            self.pos += 1;
+            self.pos
+        }
+    }
+
+    pub trait Visit {
+        fn visit(&self);
+
+        fn depth(&self) -> usize {
+            0
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<usize> {
+        input.find('{')
+    }
+}
+
+pub fn other() -> u8 {
+    1
+}
+"#;
+
+    render(
+        Renderer::default().with_expand("dummy_crate::parser::Parser::advance,**::Visit"),
+        source,
+        r#"
+            pub mod parser {
+                pub struct Parser {}
+
+                impl Parser {
+                    pub fn new() -> Self {}
+
+                    /// Advances the parser.
+                    pub fn advance(&mut self) -> usize {
+                        // Braces in strings and comments: "{" '}'
+                        self.pos += 1;
+                        self.pos
+                    }
+                }
+
+                pub trait Visit {
+                    fn visit(&self);
+
+                    fn depth(&self) -> usize {
+                        0
+                    }
+                }
+
+                pub fn parse(input: &str) -> Option<usize> {}
+            }
+
+            pub fn other() -> u8 {}
+        "#,
+        false,
+    );
+
+    render(
+        Renderer::default().with_expand("dummy_crate::parser::*"),
+        source,
+        r#"
+            pub mod parser {
+                pub struct Parser {}
+
+                impl Parser {
+                    pub fn new() -> Self {
+                        Parser { pos: 0 }
+                    }
+
+                    /// Advances the parser.
+                    pub fn advance(&mut self) -> usize {
+                        // Braces in strings and comments: "{" '}'
+                        self.pos += 1;
+                        self.pos
+                    }
+                }
+
+                pub trait Visit {
+                    fn visit(&self);
+
+                    fn depth(&self) -> usize {
+                        0
+                    }
+                }
+
+                pub fn parse(input: &str) -> Option<usize> {
+                    input.find('{')
+                }
+            }
+
+            pub fn other() -> u8 {}
+        "#,
+        false,
+    );
+}
+
+#[test]
+fn test_render_fetched_features() {
+    // Fetched crates are built as a dependency of a generated crate, which selects the features
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        r#"
+            [package]
+            name = "featured"
+            version = "0.1.0"
+            edition = "2021"
+
+            [features]
+            default = ["basic"]
+            basic = []
+            extra = []
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "#[cfg(feature = \"basic\")]\npub fn basic() {}\n#[cfg(feature = \"extra\")]\npub fn extra() {}\n",
+    )
+    .unwrap();
+    for args in [
+        &["init", "-q"][..],
+        &["add", "-A"],
+        &["commit", "-qm", "init"],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    let target = format!("git+file://{}::featured", root.display());
+    let render = |ruskel: Ruskel| ruskel.render(false, false).unwrap();
+
+    let rendered = render(Ruskel::new(&target));
+    assert!(rendered.contains("pub fn basic() {}"), "{}", rendered);
+    assert!(!rendered.contains("pub fn extra() {}"), "{}", rendered);
+
+    let rendered = render(
+        Ruskel::new(&target)
+            .with_no_default_features(true)
+            .with_features(vec!["extra".to_string()]),
+    );
+    assert!(!rendered.contains("pub fn basic() {}"), "{}", rendered);
+    assert!(rendered.contains("pub fn extra() {}"), "{}", rendered);
+
+    let rendered = render(Ruskel::new(&target).with_all_features(true));
+    assert!(rendered.contains("pub fn basic() {}"), "{}", rendered);
+    assert!(rendered.contains("pub fn extra() {}"), "{}", rendered);
+}
+
+#[test]
+fn test_render_crate_targets() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/bin")).unwrap();
+    fs::create_dir_all(root.join("examples")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        r#"
+            [package]
+            name = "dummy_bins"
+            version = "0.1.0"
+            edition = "2021"
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/main.rs"),
+        "pub fn helper() {}\nfn main() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/bin/tool.rs"),
+        "pub struct Tool;\nfn main() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("examples/demo.rs"),
+        "pub fn demo() {}\nfn main() {}\n",
+    )
+    .unwrap();
+
+    let ruskel = |target: CrateTarget| {
+        Ruskel::new(root.to_str().unwrap())
+            .with_offline(true)
+            .with_crate_target(target)
+    };
+
+    let rendered = ruskel(CrateTarget::Bin("tool".to_string()))
+        .render(false, false)
+        .unwrap();
+    assert!(rendered.contains("pub mod tool {"), "{}", rendered);
+    assert!(rendered.contains("pub struct Tool;"), "{}", rendered);
+
+    let rendered = ruskel(CrateTarget::Example("demo".to_string()))
+        .render(false, false)
+        .unwrap();
+    assert!(rendered.contains("pub fn demo() {}"), "{}", rendered);
+
+    // Without a library, the error lists what can be selected
+    let err = ruskel(CrateTarget::Lib).render(false, false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Failed to generate: package dummy_bins has no library target, available targets: \
+         bin dummy_bins, bin tool, example demo"
+    );
+}
+
+#[test]
+fn test_render_workspace() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"ws-alpha\", \"ws-beta\", \"ws-tool\"]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+    for (name, file, source) in [
+        ("ws-alpha", "lib.rs", "pub fn alpha() {}\n"),
+        ("ws-beta", "lib.rs", "pub struct Beta;\n"),
+        ("ws-tool", "main.rs", "fn main() {}\n"),
+    ] {
+        fs::create_dir_all(root.join(name).join("src")).unwrap();
+        fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+                name
+            ),
+        )
+        .unwrap();
+        fs::write(root.join(name).join("src").join(file), source).unwrap();
+    }
+
+    let ruskel = Ruskel::new(root.join("ws-beta").to_str().unwrap()).with_offline(true);
+
+    // Binary-only members are skipped
+    let rendered = ruskel.render_workspace(Renderer::default(), &[]).unwrap();
+    assert!(rendered.contains("pub mod ws_alpha {"), "{}", rendered);
+    assert!(rendered.contains("pub fn alpha() {}"), "{}", rendered);
+    assert!(rendered.contains("pub mod ws_beta {"), "{}", rendered);
+    assert!(!rendered.contains("ws_tool"), "{}", rendered);
+
+    let rendered = ruskel
+        .render_workspace(Renderer::default(), &["*-beta".to_string()])
+        .unwrap();
+    assert!(!rendered.contains("ws_alpha"), "{}", rendered);
+    assert!(rendered.contains("pub struct Beta;"), "{}", rendered);
+
+    assert!(ruskel
+        .render_workspace(Renderer::default(), &["missing".to_string()])
+        .is_err());
+}
+
+#[test]
+fn test_dependency_map() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"alpha\", \"beta\", \"gamma\"]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+    for (name, deps, source) in [
+        (
+            "alpha",
+            "",
+            "pub struct Alpha;\npub struct Config;\npub struct Internal;\npub trait Named {}\n",
+        ),
+        (
+            "beta",
+            "alpha = { path = \"../alpha\" }",
+            r#"
+            pub use alpha::Config;
+            pub struct Beta {
+                pub items: Vec<alpha::Alpha>,
+                internal: alpha::Internal,
+            }
+            impl alpha::Named for Beta {}
+            pub fn make() -> Option<alpha::Alpha> { None }
+            fn private(_: alpha::Internal) {}
+            "#,
+        ),
+        (
+            "gamma",
+            "alpha = { path = \"../alpha\" }",
+            "pub fn gamma() { let _ = alpha::Alpha; }\n",
+        ),
+    ] {
+        fs::create_dir_all(root.join(name).join("src")).unwrap();
+        fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [dependencies]\n{}\n",
+                name, deps
+            ),
+        )
+        .unwrap();
+        fs::write(root.join(name).join("src/lib.rs"), source).unwrap();
+    }
+
+    let ruskel = Ruskel::new(root.to_str().unwrap()).with_offline(true);
+    assert_eq!(
+        ruskel.dependency_map(&[]).unwrap(),
+        "beta -> alpha\n    \
+         alpha::Alpha\n        \
+         beta::Beta::items\n        \
+         beta::make\n    \
+         alpha::Config\n        \
+         beta::Config (re-export)\n    \
+         alpha::Named\n        \
+         impl alpha::Named for beta::Beta\n\
+         gamma -> alpha: not exposed\n"
+    );
+    assert_eq!(
+        ruskel.dependency_map(&["alpha".to_string()]).unwrap(),
+        "no dependencies between workspace libraries\n"
+    );
+}
+
+#[test]
+fn test_explain() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"explain\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        r#"
+        mod shapes {
+            /// A shape.
+            #[derive(Clone)]
+            pub enum Shape { Circle(f64) }
+            impl Shape {
+                pub fn area(&self) -> f64 { 0.0 }
+                pub fn scaled(&self, by: f64) -> Shape { self.clone() }
+                fn secret(&self) {}
+            }
+        }
+        pub use shapes::Shape;
+        pub mod build {
+            use crate::Shape;
+            pub fn circle(r: f64) -> Shape { Shape::Circle(r) }
+            pub fn unrelated() {}
+            fn hidden(_: Shape) {}
+            pub struct Canvas;
+            impl Canvas { pub fn draw(&self, _: &Shape) {} }
+            pub trait Drawable: Into<Shape> { fn outline(&self) -> Shape; }
+        }
+        "#,
+    )
+    .unwrap();
+    let target = |path: &str| format!("{}::{}", root.display(), path);
+
+    let output = Ruskel::new(&target("Shape"))
+        .with_offline(true)
+        .explain(Renderer::default())
+        .unwrap();
+    assert_eq!(
+        output,
+        "/// A shape.\n\
+         pub enum Shape {\n    Circle(f64),\n}\n\n\
+         // Inherent impls\n\n\
+         impl Shape {\n    pub fn area(&self) -> f64 {}\n\n    pub fn scaled(&self, by: f64) -> Shape {}\n}\n\n\
+         // Trait impls\n\n\
+         impl Clone for Shape {\n    fn clone(&self) -> Shape {}\n}\n\n\
+         // Functions that take or return Shape\n\n\
+         // explain::build::Canvas::draw\n\
+         pub fn draw(&self, _: &Shape) {}\n\n\
+         // explain::build::Drawable::outline\n\
+         fn outline(&self) -> Shape;\n\n\
+         // explain::build::circle\n\
+         pub fn circle(r: f64) -> crate::Shape {}\n\n\
+         // Traits with Shape in their bounds\n\n\
+         // explain::build::Drawable\n\
+         pub trait Drawable: Into<crate::Shape> {\n    fn outline(&self) -> Shape;\n}\n"
+    );
+
+    let private = Ruskel::new(&target("shapes::Shape"))
+        .with_offline(true)
+        .explain(Renderer::default().with_private_items(true))
+        .unwrap();
+    assert!(private.contains("fn secret(&self) {}"));
+    assert!(private.contains("// explain::build::hidden\n"));
+
+    let err = Ruskel::new(&target("build::circle"))
+        .with_offline(true)
+        .explain(Renderer::default())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid target: 'explain::build::circle' is not a type or trait"
+    );
+    assert!(Ruskel::new(&target("build::Missing"))
+        .with_offline(true)
+        .explain(Renderer::default())
+        .is_err());
+}
+
+#[test]
+fn test_uses() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"uses\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        r#"
+        #[derive(Clone)]
+        pub struct Config;
+        impl Config {
+            pub fn new() -> Self { Config }
+            pub fn merged(&self, other: &Config) -> Config { Config }
+            fn secret() -> Config { Config }
+        }
+        pub struct Builder;
+        impl Builder {
+            pub fn build(self) -> Result<Config, String> { Ok(Config) }
+        }
+        impl From<Config> for Builder {
+            fn from(_: Config) -> Self { Builder }
+        }
+        pub fn load(_: Vec<Config>) {}
+        pub struct Unused;
+        "#,
+    )
+    .unwrap();
+    let ruskel = |path: &str| Ruskel::new(&format!("{}::{}", root.display(), path));
+
+    let uses = ruskel("Config").with_offline(true).uses(false).unwrap();
+    let paths = |uses: &[libruskel::FunctionUse]| -> Vec<String> {
+        uses.iter().map(|u| u.path.clone()).collect()
+    };
+    assert_eq!(paths(&uses.constructors), ["uses::Config::new"]);
+    assert_eq!(
+        paths(&uses.methods),
+        ["uses::Builder::build", "uses::Config::merged"]
+    );
+    assert_eq!(
+        paths(&uses.consumers),
+        [
+            "<uses::Builder as From<Config>>::from",
+            "uses::Config::merged",
+            "uses::load"
+        ]
+    );
+    assert_eq!(
+        uses.to_string(),
+        "Constructors:\n    \
+         fn uses::Config::new() -> Self\n\
+         Methods returning Config:\n    \
+         fn uses::Builder::build(self) -> Result<Config, String>\n    \
+         fn uses::Config::merged(&self, other: &Config) -> Config\n\
+         Consumers:\n    \
+         fn <uses::Builder as From<Config>>::from(_: Config) -> Self\n    \
+         fn uses::Config::merged(&self, other: &Config) -> Config\n    \
+         fn uses::load(_: Vec<Config>)\n"
+    );
+
+    let private = ruskel("Config").with_offline(true).uses(true).unwrap();
+    assert_eq!(
+        paths(&private.constructors),
+        ["uses::Config::new", "uses::Config::secret"]
+    );
+
+    assert_eq!(
+        ruskel("Unused")
+            .with_offline(true)
+            .uses(false)
+            .unwrap()
+            .to_string(),
+        "no functions produce or consume Unused\n"
+    );
+    let err = ruskel("load").with_offline(true).uses(false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid target: 'uses::load' is not a type or trait"
+    );
+}
+
+#[test]
+fn test_stats() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"stats\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        r#"
+        mod inner {
+            /// Re-exported from a private module.
+            pub struct Hidden;
+        }
+        pub use inner::Hidden;
+        pub use api::Api;
+
+        pub mod api {
+            /// Documented.
+            pub struct Api;
+            impl Api {
+                pub fn get<T: Clone + Send>(&self, _: T) {}
+                /// Unsafe.
+                pub unsafe fn raw(&self) {}
+                fn private(&self) {}
+            }
+            #[deprecated]
+            pub fn old() {}
+            pub trait Source<const N: usize> where Self: Sized {
+                fn read(&self);
+            }
+        }
+
+        #[doc(hidden)]
+        pub fn internal() {}
+        "#,
+    )
+    .unwrap();
+    let ruskel = Ruskel::new(root.to_str().unwrap()).with_offline(true);
+
+    let stats = ruskel.stats(false).unwrap();
+    let paths: Vec<&str> = stats.modules.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(paths, ["stats", "stats::api"]);
+    let root_stats = &stats.modules[0];
+    assert_eq!(
+        root_stats.items,
+        [("mod", 1), ("struct", 1), ("use", 1)]
+            .map(|(k, v)| (k.to_string(), v))
+            .into()
+    );
+    let api = &stats.modules[1];
+    assert_eq!(
+        api.items,
+        [("fn", 1), ("struct", 1), ("trait", 1)]
+            .map(|(k, v)| (k.to_string(), v))
+            .into()
+    );
+    assert_eq!(api.methods, 3);
+    // old, Source, get and read lack docs
+    assert_eq!(api.undocumented, 4);
+    // Api, old, Source, get, raw and read
+    assert_eq!(api.signatures, 6);
+    assert_eq!(api.generic_params, 2);
+    assert_eq!(api.generic_bounds, 3);
+    assert_eq!(api.unsafe_functions, 1);
+    assert_eq!(api.deprecated, 1);
+    assert_eq!(stats.total.methods, 3);
+    assert_eq!(stats.total.undocumented, 5);
+
+    let table = stats.to_string();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("module      mod  struct"));
+    assert!(lines[3].starts_with("total  "));
+
+    assert_eq!(ruskel.stats(true).unwrap().modules.len(), 3);
+
+    let api_only = Ruskel::new(&format!("{}::api", root.display()))
+        .with_offline(true)
+        .stats(false)
+        .unwrap();
+    assert_eq!(api_only.modules.len(), 1);
+    assert_eq!(
+        api_only.total,
+        ModuleStats {
+            path: "total".into(),
+            ..api.clone()
+        }
+    );
+}
+
+#[test]
+fn test_doc_coverage() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"cov\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        r#"
+        //! Crate docs.
+        mod inner {
+            pub struct Reexported;
+            #[doc(hidden)]
+            pub struct Secret;
+        }
+        pub use inner::Reexported;
+        pub use inner::Secret;
+        pub use std::fmt::Display;
+
+        pub mod config {
+            //! Config docs.
+
+            /// Documented.
+            pub struct Config {
+                /// Documented.
+                pub name: String,
+                pub timeout: u64,
+                private: u8,
+            }
+            impl Config {
+                pub fn new() -> Self { unimplemented!() }
+                fn helper(&self) {}
+            }
+            #[doc(hidden)]
+            impl Config {
+                pub fn internal(&self) {}
+            }
+            /// Documented.
+            pub enum Mode {
+                /// Documented.
+                Fast,
+                Slow,
+            }
+            /// Documented.
+            pub trait Load {
+                fn load(&self);
+            }
+            #[doc(hidden)]
+            pub struct Internal;
+        }
+        "#,
+    )
+    .unwrap();
+    let ruskel = Ruskel::new(root.to_str().unwrap()).with_offline(true);
+
+    let coverage = ruskel.doc_coverage(false).unwrap();
+    assert_eq!(coverage.modules.len(), 2);
+    assert_eq!(coverage.modules[0].missing, ["cov::Reexported"]);
+    assert_eq!(
+        coverage.modules[1].missing,
+        [
+            "cov::config::Config::timeout",
+            "cov::config::Config::new",
+            "cov::config::Mode::Slow",
+            "cov::config::Load::load"
+        ]
+    );
+    assert_eq!(coverage.documented(), 7);
+    assert_eq!(coverage.total(), 12);
+    assert_eq!(
+        coverage.to_string(),
+        "module       documented  total  coverage\n\
+         cov                   1      2     50.0%\n\
+         cov::config           6     10     60.0%\n\
+         total                 7     12     58.3%\n\
+         \n\
+         Missing docs:\n    \
+         cov::Reexported\n    \
+         cov::config::Config::timeout\n    \
+         cov::config::Config::new\n    \
+         cov::config::Mode::Slow\n    \
+         cov::config::Load::load\n"
+    );
+
+    let private = ruskel.doc_coverage(true).unwrap();
+    assert!(private.modules[2]
+        .missing
+        .contains(&"cov::config::Config::private".to_string()));
+
+    let config = Ruskel::new(&format!("{}::config", root.display()))
+        .with_offline(true)
+        .doc_coverage(false)
+        .unwrap();
+    assert_eq!(config.percent(), 60.0);
+
+    // Hidden items are left out just as the skeleton leaves them out, even when rustdoc emits them
+    let hidden = Ruskel::new(root.to_str().unwrap())
+        .with_offline(true)
+        .with_hidden_items(true);
+    let skeleton = hidden.render(false, false).unwrap();
+    assert!(!skeleton.contains("Secret"), "{}", skeleton);
+    assert!(!skeleton.contains("internal"), "{}", skeleton);
+    assert_eq!(hidden.doc_coverage(false).unwrap(), coverage);
+    assert_eq!(hidden.stats(false).unwrap(), ruskel.stats(false).unwrap());
+}
+
+#[test]
+fn test_snapshot() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"snap\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn two() {}\npub fn one() {}\n",
+    )
+    .unwrap();
+    let ruskel = Ruskel::new(root.to_str().unwrap()).with_offline(true);
+    let snapshot = root.join("snapshots/api.rs.snap");
+
+    assert!(matches!(
+        libruskel::check_snapshot(&snapshot, "pub fn one() {}\n"),
+        Err(libruskel::RuskelError::SnapshotNotFound(_))
+    ));
+
+    let api = ruskel.snapshot(Renderer::default()).unwrap();
+    assert!(api.contains("pub fn one() {}"));
+    assert!(api.ends_with("}\n"));
+    libruskel::write_snapshot(&snapshot, &api).unwrap();
+    libruskel::check_snapshot(&snapshot, &api).unwrap();
+
+    // Source locations and the renderer's order never affect snapshots
+    let located = ruskel
+        .snapshot(Renderer::default().with_source_locations(SourceLocations::Absolute))
+        .unwrap();
+    assert_eq!(located, api);
+    let sorted = ruskel
+        .snapshot(Renderer::default().with_order(Order::Alphabetical))
+        .unwrap();
+    assert_eq!(sorted, api);
+    assert!(api.find("fn two").unwrap() < api.find("fn one").unwrap());
+
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn three() {}\npub fn one() {}\n",
+    )
+    .unwrap();
+    let changed = ruskel.snapshot(Renderer::default()).unwrap();
+    match libruskel::check_snapshot(&snapshot, &changed) {
+        Err(libruskel::RuskelError::SnapshotMismatch { diff, .. }) => {
+            assert!(diff.contains("-    pub fn two() {}\n"));
+            assert!(diff.contains("+    pub fn three() {}\n"));
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_render_public_paths() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"paths\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        r#"
+        mod inner {
+            pub struct Config;
+            impl Config {
+                pub fn new() -> Self { Config }
+            }
+            pub struct Settings;
+            pub mod deep {
+                pub fn helper() {}
+            }
+        }
+        pub use inner::Config;
+        pub use inner::Settings as Options;
+        pub use inner::deep as tools;
+
+        pub mod prelude {
+            pub use crate::inner::*;
+        }
+
+        #[doc(hidden)]
+        pub mod __private {
+            pub fn internal() {}
+        }
+        "#,
+    )
+    .unwrap();
+    let ruskel = Ruskel::new(root.to_str().unwrap()).with_offline(true);
+
+    let rendered = ruskel.render_public_paths(Renderer::default()).unwrap();
+    assert_eq!(
+        rendered,
+        r#"pub mod paths {
+    pub mod prelude {}
+
+    // Also available as paths::prelude::deep
+    pub mod tools {
+        pub fn helper() {}
+    }
+
+    // Also available as paths::prelude::Config
+    pub struct Config;
+
+    impl Config {
+        pub fn new() -> Self {}
+    }
+
+    // Re-exported as Options
+    // Also available as paths::prelude::Settings
+    pub struct Settings;
+}
+"#
+    );
+
+    let prelude = Ruskel::new(&format!("{}::prelude", root.display()))
+        .with_offline(true)
+        .render_public_paths(Renderer::default())
+        .unwrap();
+    assert_eq!(prelude, "pub mod prelude {}\n");
+
+    let hidden = Ruskel::new(root.to_str().unwrap())
+        .with_offline(true)
+        .with_hidden_items(true)
+        .render_public_paths(Renderer::default().with_hidden_items(true))
+        .unwrap();
+    assert!(hidden.contains("pub mod __private"));
+}
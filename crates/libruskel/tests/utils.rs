@@ -56,6 +56,21 @@ fn strip_module_declaration(s: &str) -> String {
 }
 
 pub fn render(renderer: Renderer, source: &str, expected_output: &str, is_proc_macro: bool) {
+    render_inner(renderer, source, expected_output, is_proc_macro, false);
+}
+
+/// Render roundtrip against crate data that includes `#[doc(hidden)]` items
+pub fn rt_hidden(renderer: Renderer, source: &str, expected_output: &str) {
+    render_inner(renderer, source, expected_output, false, true);
+}
+
+fn render_inner(
+    renderer: Renderer,
+    source: &str,
+    expected_output: &str,
+    is_proc_macro: bool,
+    hidden_items: bool,
+) {
     // Create a temporary directory for our dummy crate
     let temp_dir = TempDir::new().unwrap();
     let crate_path = temp_dir.path().join("src");
@@ -87,10 +102,15 @@ pub fn render(renderer: Renderer, source: &str, expected_output: &str, is_proc_m
     fs::write(temp_dir.path().join("Cargo.toml"), cargo_toml_content).unwrap();
 
     // Parse the crate using Ruskel
-    let ruskel = Ruskel::new(temp_dir.path().to_str().unwrap()).with_offline(true);
+    let ruskel = Ruskel::new(temp_dir.path().to_str().unwrap())
+        .with_offline(true)
+        .with_hidden_items(hidden_items);
     let crate_data = ruskel.make_crate().unwrap();
 
-    // Render the crate data
+    // Render the crate data, resolving source spans against the dummy crate
+    let renderer = renderer
+        .with_source_root(temp_dir.path())
+        .with_package_root(temp_dir.path());
     let normalized_rendered = normalize_whitespace(&strip_module_declaration(
         &renderer.render(&crate_data).unwrap(),
     ));
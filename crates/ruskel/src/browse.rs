@@ -0,0 +1,704 @@
+//! An interactive terminal browser for a crate: a module tree, the items of the selected module,
+//! and the selected item's rendered signature and docs, with links to the types it uses.
+
+use std::collections::HashMap;
+use std::io;
+
+use libruskel::{ItemKind, LoadedCrate, Renderer, SignatureLink};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
+
+/// Runs the browser until the user quits, starting at the path the target named, if any.
+pub fn run(loaded: &LoadedCrate, private: bool) -> io::Result<()> {
+    let mut browser = Browser::new(loaded, private);
+    if let Some(path) = loaded.target_path() {
+        browser.select_path(path);
+    }
+
+    let mut terminal = ratatui::try_init()?;
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| browser.draw(frame)) {
+            break Err(e);
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                if browser.handle_key(key) {
+                    break Ok(());
+                }
+            }
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    ratatui::restore();
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Modules,
+    Items,
+    Links,
+}
+
+/// A module in the tree, which is stored in pre-order.
+struct ModuleNode {
+    path: String,
+    name: String,
+    depth: usize,
+    parent: Option<usize>,
+    expanded: bool,
+    docs: String,
+}
+
+/// An item listed in a module.
+struct Entry {
+    id: Id,
+    path: String,
+    name: String,
+    kind: ItemKind,
+    module: usize,
+}
+
+pub struct Browser<'a> {
+    loaded: &'a LoadedCrate,
+    crate_data: &'a Crate,
+    private: bool,
+    modules: Vec<ModuleNode>,
+    entries: Vec<Entry>,
+    /// The entry each local item is listed as.
+    locations: HashMap<Id, usize>,
+    focus: Focus,
+    module: usize,
+    item_cursor: usize,
+    link_cursor: usize,
+    detail_scroll: u16,
+    /// The search query, which replaces the module's items with matches from the whole crate.
+    search: Option<String>,
+    /// Whether the search query is being typed.
+    searching: bool,
+    /// Where to return to after following a link: module, item cursor and focus.
+    history: Vec<(usize, usize, Focus)>,
+    message: String,
+    /// Rendered items, by entry.
+    rendered: HashMap<usize, String>,
+}
+
+impl<'a> Browser<'a> {
+    pub fn new(loaded: &'a LoadedCrate, private: bool) -> Self {
+        let crate_data = loaded.crate_data();
+        let mut browser = Browser {
+            loaded,
+            crate_data,
+            private,
+            modules: Vec::new(),
+            entries: Vec::new(),
+            locations: HashMap::new(),
+            focus: Focus::Modules,
+            module: 0,
+            item_cursor: 0,
+            link_cursor: 0,
+            detail_scroll: 0,
+            search: None,
+            searching: false,
+            history: Vec::new(),
+            message: String::new(),
+            rendered: HashMap::new(),
+        };
+        if let Some(root) = crate_data.index.get(&crate_data.root) {
+            browser.add_module(root, "", None);
+            browser.modules[0].expanded = true;
+        }
+        // Items only public through a re-export are found at the re-export
+        for (index, entry) in browser.entries.iter().enumerate() {
+            if let Some(ItemEnum::Import(import)) =
+                crate_data.index.get(&entry.id).map(|item| &item.inner)
+            {
+                if let Some(target) = &import.id {
+                    browser.locations.entry(target.clone()).or_insert(index);
+                }
+            }
+        }
+        browser
+    }
+
+    fn add_module(&mut self, item: &Item, parent_path: &str, parent: Option<usize>) {
+        let name = item.name.clone().unwrap_or_default();
+        let path = if parent_path.is_empty() {
+            name.clone()
+        } else {
+            format!("{}::{}", parent_path, name)
+        };
+        let index = self.modules.len();
+        self.modules.push(ModuleNode {
+            path: path.clone(),
+            name,
+            depth: parent.map_or(0, |p| self.modules[p].depth + 1),
+            parent,
+            expanded: false,
+            docs: item.docs.clone().unwrap_or_default(),
+        });
+
+        let ItemEnum::Module(module) = &item.inner else {
+            return;
+        };
+        for id in &module.items {
+            let Some(child) = self.crate_data.index.get(id) else {
+                continue;
+            };
+            if !self.private && !matches!(child.visibility, Visibility::Public) {
+                continue;
+            }
+            let Some(kind) = ItemKind::of(child) else {
+                continue;
+            };
+            let name = match &child.inner {
+                ItemEnum::Import(import) => import.name.clone(),
+                _ => child.name.clone().unwrap_or_default(),
+            };
+            self.locations.insert(id.clone(), self.entries.len());
+            self.entries.push(Entry {
+                id: id.clone(),
+                path: format!("{}::{}", path, name),
+                name,
+                kind,
+                module: index,
+            });
+            if kind == ItemKind::Module {
+                self.add_module(child, &path, Some(index));
+            }
+        }
+    }
+
+    /// The modules shown in the tree, whose ancestors are all expanded.
+    fn visible_modules(&self) -> Vec<usize> {
+        (0..self.modules.len())
+            .filter(|&i| {
+                let mut parent = self.modules[i].parent;
+                while let Some(p) = parent {
+                    if !self.modules[p].expanded {
+                        return false;
+                    }
+                    parent = self.modules[p].parent;
+                }
+                true
+            })
+            .collect()
+    }
+
+    /// The entries in the item list: the selected module's items, or the search matches.
+    fn items(&self) -> Vec<usize> {
+        match &self.search {
+            Some(query) => {
+                let query = query.to_lowercase();
+                (0..self.entries.len())
+                    .filter(|&i| {
+                        let entry = &self.entries[i];
+                        let haystack = if query.contains("::") {
+                            &entry.path
+                        } else {
+                            &entry.name
+                        };
+                        haystack.to_lowercase().contains(&query)
+                    })
+                    .collect()
+            }
+            None => (0..self.entries.len())
+                .filter(|&i| self.entries[i].module == self.module)
+                .collect(),
+        }
+    }
+
+    fn selected_entry(&self) -> Option<usize> {
+        self.items().get(self.item_cursor).copied()
+    }
+
+    fn links(&self) -> Vec<SignatureLink> {
+        let Some(entry) = self.selected_entry() else {
+            return Vec::new();
+        };
+        self.loaded
+            .signature_links(&self.entries[entry].id, self.private)
+    }
+
+    /// Selects the item or module with the given path.
+    pub fn select_path(&mut self, path: &str) {
+        if let Some(index) = self.entries.iter().position(|e| e.path == path) {
+            self.show_entry(index);
+        } else if let Some(module) = self.modules.iter().position(|m| m.path == path) {
+            self.select_module(module);
+        } else {
+            self.message = format!("{} not found", path);
+        }
+    }
+
+    fn select_module(&mut self, module: usize) {
+        let mut parent = self.modules[module].parent;
+        while let Some(p) = parent {
+            self.modules[p].expanded = true;
+            parent = self.modules[p].parent;
+        }
+        self.module = module;
+        self.search = None;
+        self.searching = false;
+        self.item_cursor = 0;
+        self.link_cursor = 0;
+        self.detail_scroll = 0;
+    }
+
+    /// Shows an entry in its module's item list.
+    fn show_entry(&mut self, index: usize) {
+        self.select_module(self.entries[index].module);
+        self.item_cursor = self.items().iter().position(|&i| i == index).unwrap_or(0);
+        self.focus = Focus::Items;
+    }
+
+    /// Opens an item: modules are shown in the tree, and other items' links are focused.
+    fn open_item(&mut self) {
+        let Some(index) = self.selected_entry() else {
+            return;
+        };
+        let entry = &self.entries[index];
+        if entry.kind == ItemKind::Module {
+            let path = entry.path.clone();
+            self.push_history();
+            self.select_path_module(&path);
+        } else if self.links().is_empty() {
+            self.message = format!("{} uses no other types", entry.path);
+        } else {
+            self.focus = Focus::Links;
+            self.link_cursor = 0;
+        }
+    }
+
+    fn select_path_module(&mut self, path: &str) {
+        if let Some(module) = self.modules.iter().position(|m| m.path == path) {
+            self.select_module(module);
+            self.focus = Focus::Modules;
+        }
+    }
+
+    /// Jumps to the definition of the selected link.
+    fn follow_link(&mut self) {
+        let links = self.links();
+        let Some(link) = links.get(self.link_cursor) else {
+            return;
+        };
+        match self.locations.get(&link.id) {
+            Some(&index) => {
+                self.push_history();
+                self.show_entry(index);
+            }
+            None if link.local => self.message = format!("{} isn't listed", link.path),
+            None => self.message = format!("{} is defined in another crate", link.path),
+        }
+    }
+
+    fn push_history(&mut self) {
+        self.history
+            .push((self.module, self.item_cursor, self.focus));
+    }
+
+    fn back(&mut self) {
+        if let Some((module, item_cursor, focus)) = self.history.pop() {
+            self.select_module(module);
+            self.item_cursor = item_cursor;
+            self.focus = focus;
+        }
+    }
+
+    fn move_cursor(&mut self, down: bool) {
+        let step = |cursor: usize, len: usize| {
+            if down {
+                (cursor + 1).min(len.saturating_sub(1))
+            } else {
+                cursor.saturating_sub(1)
+            }
+        };
+        match self.focus {
+            Focus::Modules => {
+                let visible = self.visible_modules();
+                let position = visible.iter().position(|&m| m == self.module).unwrap_or(0);
+                let module = visible[step(position, visible.len())];
+                if module != self.module {
+                    self.select_module(module);
+                }
+            }
+            Focus::Items => {
+                let cursor = step(self.item_cursor, self.items().len());
+                if cursor != self.item_cursor {
+                    self.item_cursor = cursor;
+                    self.link_cursor = 0;
+                    self.detail_scroll = 0;
+                }
+            }
+            Focus::Links => self.link_cursor = step(self.link_cursor, self.links().len()),
+        }
+    }
+
+    /// Handles a key press, returning true if the browser should exit.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.message.clear();
+        if self.searching {
+            let query = self.search.get_or_insert_with(String::new);
+            match key.code {
+                KeyCode::Char(c) => query.push(c),
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.search = None;
+                    self.searching = false;
+                }
+                _ => {}
+            }
+            self.item_cursor = 0;
+            self.link_cursor = 0;
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Esc if self.search.is_some() => {
+                self.search = None;
+                self.item_cursor = 0;
+            }
+            KeyCode::Esc => return true,
+            KeyCode::Char('/') => {
+                self.search = Some(String::new());
+                self.searching = true;
+                self.focus = Focus::Items;
+                self.item_cursor = 0;
+            }
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Modules => Focus::Items,
+                    Focus::Items => Focus::Links,
+                    Focus::Links => Focus::Modules,
+                }
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Modules => Focus::Links,
+                    Focus::Items => Focus::Modules,
+                    Focus::Links => Focus::Items,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(false),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(true),
+            KeyCode::PageUp => self.detail_scroll = self.detail_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.detail_scroll = self.detail_scroll.saturating_add(10),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => match self.focus {
+                Focus::Modules => {
+                    let node = &mut self.modules[self.module];
+                    node.expanded = key.code != KeyCode::Enter || !node.expanded;
+                }
+                Focus::Items => self.open_item(),
+                Focus::Links => self.follow_link(),
+            },
+            KeyCode::Left | KeyCode::Char('h') => match self.focus {
+                Focus::Modules => {
+                    let node = &mut self.modules[self.module];
+                    if node.expanded && node.parent.is_some() {
+                        node.expanded = false;
+                    } else if let Some(parent) = node.parent {
+                        self.select_module(parent);
+                    }
+                }
+                Focus::Items => self.focus = Focus::Modules,
+                Focus::Links => self.focus = Focus::Items,
+            },
+            KeyCode::Backspace | KeyCode::Char('b') => self.back(),
+            _ => {}
+        }
+        false
+    }
+
+    /// Renders an entry the way ruskel prints it, without the enclosing modules.
+    fn render_entry(&mut self, index: usize) -> &str {
+        let entry = &self.entries[index];
+        let (path, depth) = (entry.path.clone(), entry.path.split("::").count() - 1);
+        let (crate_data, private) = (self.crate_data, self.private);
+        self.rendered.entry(index).or_insert_with(|| {
+            Renderer::default()
+                .with_private_items(private)
+                .with_filter(&path)
+                .render(crate_data)
+                .map(|output| strip_modules(&output, depth))
+                .unwrap_or_else(|e| e.to_string())
+        })
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [modules_area, items_area, detail_area] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+            Constraint::Percentage(45),
+        ])
+        .areas(main);
+
+        let current = self.focus;
+        let highlight = |focus: Focus| {
+            if current == focus {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            }
+        };
+
+        let visible = self.visible_modules();
+        let module_items: Vec<ListItem> = visible
+            .iter()
+            .map(|&m| {
+                let node = &self.modules[m];
+                let has_children = self.modules.iter().any(|n| n.parent == Some(m));
+                let marker = match (has_children, node.expanded) {
+                    (false, _) => "  ",
+                    (true, true) => "▾ ",
+                    (true, false) => "▸ ",
+                };
+                ListItem::new(format!(
+                    "{}{}{}",
+                    "  ".repeat(node.depth),
+                    marker,
+                    node.name
+                ))
+            })
+            .collect();
+        let mut state =
+            ListState::default().with_selected(visible.iter().position(|&m| m == self.module));
+        frame.render_stateful_widget(
+            List::new(module_items)
+                .block(Block::bordered().title("Modules"))
+                .highlight_style(highlight(Focus::Modules)),
+            modules_area,
+            &mut state,
+        );
+
+        let items = self.items();
+        let item_list: Vec<ListItem> = items
+            .iter()
+            .map(|&i| {
+                let entry = &self.entries[i];
+                let name = if self.search.is_some() {
+                    &entry.path
+                } else {
+                    &entry.name
+                };
+                ListItem::new(format!("{:<6} {}", entry.kind.name(), name))
+            })
+            .collect();
+        let title = match &self.search {
+            Some(query) => format!("Search: {}", query),
+            None => format!("Items in {}", self.modules[self.module].path),
+        };
+        let mut state = ListState::default().with_selected(Some(self.item_cursor));
+        frame.render_stateful_widget(
+            List::new(item_list)
+                .block(Block::bordered().title(title))
+                .highlight_style(highlight(Focus::Items)),
+            items_area,
+            &mut state,
+        );
+
+        let links = self.links();
+        let links_height = (links.len() as u16 + 2).clamp(3, 10);
+        let [text_area, links_area] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(links_height)])
+                .areas(detail_area);
+        let (title, text) = match self.selected_entry() {
+            Some(index) => (
+                self.entries[index].path.clone(),
+                self.render_entry(index).to_string(),
+            ),
+            None => (
+                self.modules[self.module].path.clone(),
+                self.modules[self.module].docs.clone(),
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .block(Block::bordered().title(title))
+                .scroll((self.detail_scroll, 0)),
+            text_area,
+        );
+
+        let link_items: Vec<ListItem> = links
+            .iter()
+            .map(|link| {
+                if link.local {
+                    ListItem::new(link.path.clone())
+                } else {
+                    ListItem::new(link.path.clone())
+                        .style(Style::default().add_modifier(Modifier::DIM))
+                }
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.link_cursor));
+        frame.render_stateful_widget(
+            List::new(link_items)
+                .block(Block::bordered().title("Uses"))
+                .highlight_style(highlight(Focus::Links)),
+            links_area,
+            &mut state,
+        );
+
+        let status_line = if self.searching {
+            format!("/{}", self.search.as_deref().unwrap_or_default())
+        } else if !self.message.is_empty() {
+            self.message.clone()
+        } else {
+            "q quit  / search  tab switch pane  enter open  b back  pgup/pgdn scroll".to_string()
+        };
+        frame.render_widget(Line::from(status_line), status);
+    }
+}
+
+/// Removes the `mod` blocks that enclose an item rendered `depth` modules deep.
+fn strip_modules(rendered: &str, depth: usize) -> String {
+    let lines: Vec<&str> = rendered.trim_end().lines().collect();
+    if lines.len() < depth * 2 {
+        return rendered.to_string();
+    }
+    let inner = &lines[depth..lines.len() - depth];
+    let indent = depth * 4;
+    inner
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libruskel::Ruskel;
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::fs;
+
+    fn press(browser: &mut Browser, codes: &[KeyCode]) {
+        for &code in codes {
+            assert!(!browser.handle_key(KeyEvent::from(code)));
+        }
+    }
+
+    fn selected_path(browser: &Browser) -> String {
+        browser.entries[browser.selected_entry().unwrap()]
+            .path
+            .clone()
+    }
+
+    #[test]
+    fn test_browser() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"browse_test\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod shapes {\n    /// A circle.\n    pub struct Circle { pub radius: crate::Unit }\n    pub fn area(c: &Circle) -> String { String::new() }\n    pub mod solid {\n        pub struct Sphere;\n    }\n}\npub mod units {\n    pub struct Unit;\n}\npub use units::Unit;\nfn hidden() {}\n",
+        )
+        .unwrap();
+        let loaded = Ruskel::new(root.to_str().unwrap())
+            .with_offline(true)
+            .load()
+            .unwrap();
+        let mut browser = Browser::new(&loaded, false);
+
+        let tree: Vec<&str> = browser.modules.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(
+            tree,
+            [
+                "browse_test",
+                "browse_test::shapes",
+                "browse_test::shapes::solid",
+                "browse_test::units"
+            ]
+        );
+        assert_eq!(
+            browser.visible_modules(),
+            [0, 1, 3],
+            "only the root is expanded"
+        );
+        assert!(!browser.entries.iter().any(|e| e.name == "hidden"));
+
+        // Moving down the tree selects shapes and lists its items, submodules first
+        press(&mut browser, &[KeyCode::Down, KeyCode::Tab]);
+        assert_eq!(selected_path(&browser), "browse_test::shapes::solid");
+        press(&mut browser, &[KeyCode::Down]);
+        assert_eq!(selected_path(&browser), "browse_test::shapes::Circle");
+        assert!(browser
+            .render_entry(browser.selected_entry().unwrap())
+            .starts_with("/// A circle.\npub struct Circle {"));
+
+        // Following a link from area jumps to Circle, and back returns
+        press(&mut browser, &[KeyCode::Down]);
+        assert_eq!(selected_path(&browser), "browse_test::shapes::area");
+        let links: Vec<String> = browser.links().into_iter().map(|l| l.path).collect();
+        assert_eq!(
+            links,
+            ["browse_test::shapes::Circle", "alloc::string::String"]
+        );
+        press(
+            &mut browser,
+            &[KeyCode::Enter, KeyCode::Down, KeyCode::Enter],
+        );
+        assert!(browser.message.contains("another crate"));
+        press(&mut browser, &[KeyCode::Up, KeyCode::Enter]);
+        assert_eq!(selected_path(&browser), "browse_test::shapes::Circle");
+        assert_eq!(browser.history.len(), 1);
+
+        // Circle's field type is reached through its links
+        press(&mut browser, &[KeyCode::Char('l'), KeyCode::Enter]);
+        assert_eq!(selected_path(&browser), "browse_test::units::Unit");
+        press(&mut browser, &[KeyCode::Char('b'), KeyCode::Char('b')]);
+        assert_eq!(selected_path(&browser), "browse_test::shapes::area");
+        assert_eq!(browser.focus, Focus::Links);
+
+        // Search lists matches from the whole crate until cleared
+        press(&mut browser, &[KeyCode::Char('/')]);
+        for c in "SPH".chars() {
+            press(&mut browser, &[KeyCode::Char(c)]);
+        }
+        press(&mut browser, &[KeyCode::Enter]);
+        assert_eq!(
+            selected_path(&browser),
+            "browse_test::shapes::solid::Sphere"
+        );
+        press(&mut browser, &[KeyCode::Esc]);
+        assert!(browser.search.is_none());
+        assert!(browser.handle_key(KeyEvent::from(KeyCode::Esc)));
+
+        browser.select_path("browse_test::shapes::solid::Sphere");
+        assert_eq!(browser.module, 2);
+        assert!(browser.visible_modules().contains(&2));
+        browser.select_path("browse_test::missing");
+        assert_eq!(browser.message, "browse_test::missing not found");
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("▾ shapes"));
+        assert!(screen.contains("pub struct Sphere;"));
+    }
+}
@@ -1,54 +1,250 @@
-use clap::Parser;
-use libruskel::Ruskel;
+use clap::{Parser, Subcommand};
+use libruskel::{CrateTarget, Deprecated, ItemKind, Order, Renderer, Ruskel, SourceLocations};
 use std::io::{self, IsTerminal, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+mod browse;
+mod mcp;
+mod serve;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Target to generate - a directory, file path, or a module name
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// Target to generate - a directory, file path, or a module name. Targets named like a
+    /// subcommand must follow `--`, e.g. `ruskel -- stats`
     #[arg(default_value = ".")]
     target: String,
 
+    /// Document the package's library (the default)
+    #[arg(long, global = true, default_value_t = false, group = "crate_target")]
+    lib: bool,
+
+    /// Document the named binary target of the package
+    #[arg(long, global = true, value_name = "NAME", group = "crate_target")]
+    bin: Option<String>,
+
+    /// Document the named example of the package
+    #[arg(long, global = true, value_name = "NAME", group = "crate_target")]
+    example: Option<String>,
+
+    /// Render the libraries of all members of the workspace containing the target directory
+    #[arg(long, default_value_t = false, conflicts_with_all = ["raw", "crate_target"])]
+    workspace: bool,
+
+    /// Only render workspace members matching this name pattern, e.g. `mycrate-*` (repeatable)
+    #[arg(long, value_name = "GLOB", requires = "workspace")]
+    package: Vec<String>,
+
+    /// With --workspace, report which items of each member's workspace dependencies appear in
+    /// its public API
+    #[arg(long, default_value_t = false, requires = "workspace")]
+    dependency_map: bool,
+
+    /// Organize output by public path: each item is rendered once, in the module of the shortest
+    /// path it can be named by, with comments noting where else it's re-exported
+    #[arg(long, default_value_t = false, conflicts_with_all = ["raw", "workspace"])]
+    public_paths: bool,
+
     /// Output raw JSON instead of rendered Rust code
     #[arg(long, default_value_t = false)]
     raw: bool,
 
     /// Render auto-implemented traits
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     auto_impls: bool,
 
     /// Render private items
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     private: bool,
 
+    /// Render items marked #[doc(hidden)]
+    #[arg(long, global = true, default_value_t = false)]
+    show_hidden: bool,
+
+    /// How to render deprecated items: inline, hide, or last (grouped at the end of each module)
+    #[arg(long, global = true, default_value = "inline", value_name = "MODE")]
+    deprecated: Deprecated,
+
+    /// Order of items within modules, impls and traits: rustdoc (as rustdoc lists them), source,
+    /// alphabetical (within each kind), or kind (grouped by kind, in source order)
+    #[arg(long, global = true, default_value = "rustdoc", value_name = "ORDER")]
+    order: Order,
+
+    /// Annotate items with their source location: off, relative (to the package root), or absolute
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_value = "off",
+        default_missing_value = "relative"
+    )]
+    source_locations: SourceLocations,
+
+    /// Render the original bodies of functions matching a pattern, e.g. `mycrate::parser::*`
+    #[arg(long, global = true, value_name = "PATTERN")]
+    expand: Option<String>,
+
+    /// Exclude items matching a pattern, e.g. `__private`, `crate::tests` or `macro:*` (repeatable)
+    #[arg(long, global = true, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
+    /// Only render items of these kinds: mod, struct, enum, trait, fn, const, type, macro, use
+    #[arg(long, global = true, value_delimiter = ',', value_name = "KINDS")]
+    kinds: Vec<ItemKind>,
+
     /// Disable default features
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     no_default_features: bool,
 
     /// Enable all features
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     all_features: bool,
 
     /// Specify features to enable
-    #[arg(long, value_delimiter = ',')]
+    #[arg(long, global = true, value_delimiter = ',')]
     features: Vec<String>,
 
     /// Enable syntax highlighting
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     highlight: bool,
 
     /// Disable syntax highlighting
-    #[arg(long, default_value_t = false, conflicts_with = "highlight")]
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        conflicts_with = "highlight"
+    )]
     no_highlight: bool,
 
     /// Disable paging
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     no_page: bool,
 
     /// Enable offline mode, ensuring Cargo will not use the network
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     offline: bool,
+
+    /// Fetch crates from an alternative registry configured in .cargo/config.toml
+    #[arg(long, global = true, value_name = "NAME")]
+    registry: Option<String>,
+
+    /// Use a directory created by `cargo vendor` in place of crates.io
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        conflicts_with = "local_registry"
+    )]
+    vendor_dir: Option<PathBuf>,
+
+    /// Use a local registry directory in place of crates.io
+    #[arg(long, global = true, value_name = "DIR")]
+    local_registry: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Run a Model Context Protocol server on stdin and stdout, exposing skeleton, search and item
+    /// tools. Source options such as --offline and --vendor-dir apply to every call.
+    Mcp,
+
+    /// Run an HTTP server with a JSON API, keeping built crates in memory and rebuilding local
+    /// packages when their sources change. Endpoints: /render, /search, /raw and /cache, with
+    /// options as query parameters, e.g. /render?target=serde&private=true.
+    Serve {
+        /// The address to listen on
+        #[arg(long, default_value = "127.0.0.1:7878", value_name = "ADDR")]
+        listen: String,
+    },
+
+    /// Render a type or trait with everything related to it: its inherent and trait impls, the
+    /// functions in the crate that take or return it, and the traits it appears in the bounds of.
+    Item {
+        /// Path to the type, e.g. serde_json::Value or /path/to/crate::module::Type
+        path: String,
+    },
+
+    /// List the functions that return or take a type: constructors, methods returning it, and
+    /// consumers
+    Uses {
+        /// Path to the type, e.g. reqwest::Client or /path/to/crate::module::Type
+        path: String,
+    },
+
+    /// Report API metrics for each public module of the target: item counts by kind,
+    /// undocumented items, generic parameters and bounds per signature, unsafe functions and
+    /// deprecated items
+    Stats {
+        /// Target to report on, in any form accepted by ruskel; defaults to the main target
+        target: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: StatsFormat,
+    },
+
+    /// List public items without docs, including struct fields, enum variants and trait items,
+    /// with coverage per module
+    DocCoverage {
+        /// Target to check, in any form accepted by ruskel; defaults to the main target
+        target: Option<String>,
+
+        /// Exit with an error if overall coverage is below this percentage
+        #[arg(long, value_name = "PERCENT")]
+        min: Option<f64>,
+    },
+
+    /// Write the rendered public API to a snapshot file, or check it against one and show a diff
+    /// if it has changed. Snapshots have no source locations or highlighting.
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+
+        /// The snapshot file
+        #[arg(
+            long,
+            global = true,
+            default_value = "api.rs.snap",
+            value_name = "FILE"
+        )]
+        file: PathBuf,
+    },
+
+    /// Browse a crate interactively in the terminal: its module tree, each item's rendered
+    /// skeleton, and the types that item uses. Press / to search and b to go back.
+    Browse {
+        /// Target to browse, in any form accepted by ruskel; defaults to the main target
+        target: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Write the current API to the snapshot file
+    Write {
+        /// Target to snapshot, in any form accepted by ruskel; defaults to the main target
+        target: Option<String>,
+    },
+    /// Fail with a diff if the current API differs from the snapshot file
+    Check {
+        /// Target to check, in any form accepted by ruskel; defaults to the main target
+        target: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum StatsFormat {
+    Table,
+    Json,
 }
 
 fn main() {
@@ -67,17 +263,84 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         cli.highlight || io::stdout().is_terminal()
     };
 
-    let rs = Ruskel::new(&cli.target)
-        .with_offline(cli.offline)
-        .with_no_default_features(cli.no_default_features)
-        .with_all_features(cli.all_features)
-        .with_features(cli.features)
-        .with_highlighting(should_highlight);
+    if let Some(Commands::Mcp) = cli.command {
+        mcp::serve(io::stdin().lock(), io::stdout(), |target| {
+            with_sources(Ruskel::new(target), &cli)
+        })?;
+        return Ok(());
+    }
+    if let Some(Commands::Serve { listen }) = &cli.command {
+        let listener = TcpListener::bind(listen)?;
+        eprintln!("listening on http://{}", listener.local_addr()?);
+        let cli = Arc::new(cli);
+        let server = serve::Server::new(Box::new(move |target| {
+            with_sources(Ruskel::new(target), &cli)
+        }));
+        Arc::new(server).run(listener)?;
+        return Ok(());
+    }
+
+    if let Some(Commands::Browse { target }) = &cli.command {
+        let loaded = ruskel(target.as_deref().unwrap_or(&cli.target), &cli).load()?;
+        browse::run(&loaded, cli.private)?;
+        return Ok(());
+    }
 
-    let output = if cli.raw {
-        rs.raw_json()?
+    if let Some(Commands::Snapshot { action, file }) = &cli.command {
+        let (SnapshotAction::Write { target } | SnapshotAction::Check { target }) = action;
+        let target = target.as_deref().unwrap_or(&cli.target);
+        let api = ruskel(target, &cli).snapshot(renderer(&cli))?;
+        match action {
+            SnapshotAction::Write { .. } => {
+                libruskel::write_snapshot(file, &api)?;
+                eprintln!("wrote {}", file.display());
+            }
+            SnapshotAction::Check { .. } => {
+                libruskel::check_snapshot(file, &api)?;
+                eprintln!("API matches {}", file.display());
+            }
+        }
+        return Ok(());
+    }
+
+    let mut failure = None;
+    let output = if let Some(Commands::DocCoverage { target, min }) = &cli.command {
+        let target = target.as_deref().unwrap_or(&cli.target);
+        let coverage = ruskel(target, &cli).doc_coverage(cli.private)?;
+        if let Some(min) = min.filter(|&min| coverage.percent() < min) {
+            failure = Some(format!(
+                "documentation coverage {:.1}% is below the minimum of {}%",
+                coverage.percent(),
+                min
+            ));
+        }
+        coverage.to_string()
+    } else if let Some(Commands::Stats { target, format }) = &cli.command {
+        let target = target.as_deref().unwrap_or(&cli.target);
+        let stats = ruskel(target, &cli).stats(cli.private)?;
+        match format {
+            StatsFormat::Table => stats.to_string(),
+            StatsFormat::Json => serde_json::to_string_pretty(&stats)?,
+        }
+    } else if let Some(Commands::Uses { path }) = &cli.command {
+        ruskel(path, &cli).uses(cli.private)?.to_string()
+    } else if let Some(Commands::Item { path }) = &cli.command {
+        ruskel(path, &cli)
+            .with_highlighting(should_highlight)
+            .explain(renderer(&cli))?
     } else {
-        rs.render(cli.auto_impls, cli.private)?
+        let rs = ruskel(&cli.target, &cli).with_highlighting(should_highlight);
+        if cli.raw {
+            rs.raw_json()?
+        } else if cli.public_paths {
+            rs.render_public_paths(renderer(&cli))?
+        } else if cli.dependency_map {
+            rs.dependency_map(&cli.package)?
+        } else if cli.workspace {
+            rs.render_workspace(renderer(&cli), &cli.package)?
+        } else {
+            rs.render_with(renderer(&cli))?
+        }
     };
 
     if io::stdout().is_terminal() && !cli.no_page {
@@ -86,7 +349,60 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         println!("{}", output);
     }
 
-    Ok(())
+    match failure {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+/// Creates a renderer with the options that control what is rendered and how.
+fn renderer(cli: &Cli) -> Renderer {
+    let mut renderer = Renderer::default()
+        .with_auto_impls(cli.auto_impls)
+        .with_private_items(cli.private)
+        .with_hidden_items(cli.show_hidden)
+        .with_deprecated(cli.deprecated)
+        .with_order(cli.order)
+        .with_source_locations(cli.source_locations)
+        .with_kinds(&cli.kinds);
+    if let Some(expand) = &cli.expand {
+        renderer = renderer.with_expand(expand);
+    }
+    for exclude in &cli.exclude {
+        renderer = renderer.with_exclude(exclude);
+    }
+    renderer
+}
+
+/// Creates a Ruskel for the target with the options that select what to build.
+fn ruskel(target: &str, cli: &Cli) -> Ruskel {
+    let crate_target = match (&cli.bin, &cli.example) {
+        (Some(name), _) => CrateTarget::Bin(name.clone()),
+        (_, Some(name)) => CrateTarget::Example(name.clone()),
+        _ => CrateTarget::Lib,
+    };
+    let rs = Ruskel::new(target)
+        .with_crate_target(crate_target)
+        .with_no_default_features(cli.no_default_features)
+        .with_all_features(cli.all_features)
+        .with_features(cli.features.clone())
+        .with_hidden_items(cli.show_hidden);
+    with_sources(rs, cli)
+}
+
+/// Applies the options that control where crates are fetched from.
+fn with_sources(mut rs: Ruskel, cli: &Cli) -> Ruskel {
+    rs = rs.with_offline(cli.offline);
+    if let Some(registry) = &cli.registry {
+        rs = rs.with_registry(registry);
+    }
+    if let Some(dir) = &cli.vendor_dir {
+        rs = rs.with_vendor_dir(dir);
+    }
+    if let Some(dir) = &cli.local_registry {
+        rs = rs.with_local_registry(dir);
+    }
+    rs
 }
 
 fn page_output(content: &str) -> io::Result<()> {
@@ -100,3 +416,57 @@ fn page_output(content: &str) -> io::Result<()> {
     child.wait()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subcommand_named_target() {
+        let cli = Cli::try_parse_from(["ruskel", "stats"]).unwrap();
+        assert!(matches!(cli.command, Some(Commands::Stats { .. })));
+        assert_eq!(cli.target, ".");
+
+        let cli = Cli::try_parse_from(["ruskel", "--", "stats"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.target, "stats");
+
+        let cli = Cli::try_parse_from(["ruskel", "--private", "--", "item"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.target, "item");
+    }
+
+    #[test]
+    fn test_subcommand_options() {
+        // Shared options are accepted after the subcommand, and its target replaces the main one
+        let cli =
+            Cli::try_parse_from(["ruskel", "stats", "serde", "--private", "--features", "a,b"])
+                .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Stats { target: Some(ref target), .. }) if target == "serde"
+        ));
+        assert!(cli.private);
+        assert_eq!(cli.features, ["a", "b"]);
+
+        let cli =
+            Cli::try_parse_from(["ruskel", "doc-coverage", "--all-features", "--offline"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::DocCoverage { target: None, .. })
+        ));
+        assert!(cli.all_features && cli.offline);
+
+        let cli =
+            Cli::try_parse_from(["ruskel", "snapshot", "check", "serde", "--order", "source"])
+                .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Snapshot {
+                action: SnapshotAction::Check { target: Some(ref target) },
+                ..
+            }) if target == "serde"
+        ));
+        assert!(matches!(cli.order, Order::Source));
+    }
+}
@@ -0,0 +1,374 @@
+//! A Model Context Protocol server that exposes ruskel as tools, speaking newline-delimited
+//! JSON-RPC over stdin and stdout.
+
+use std::io::{self, BufRead, Write};
+
+use libruskel::{Renderer, Ruskel};
+use serde_json::{json, Map, Value};
+
+/// Protocol versions we can speak, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A JSON-RPC error code and message.
+type RpcError = (i64, String);
+
+/// Serves requests until the input is closed. `ruskel` creates a `Ruskel` for a target, with any
+/// options that apply to every call, such as offline mode, already set.
+pub fn serve<R, W, F>(input: R, mut output: W, ruskel: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: Fn(&str) -> Ruskel,
+{
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(&line, &ruskel) {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handles one message, returning the response, or None for notifications.
+fn handle_message(line: &str, ruskel: &dyn Fn(&str) -> Ruskel) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, (PARSE_ERROR, e.to_string()))),
+    };
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            (INVALID_REQUEST, "missing method".to_string()),
+        ));
+    };
+    // Notifications, such as notifications/initialized, need no response
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(&params, ruskel),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, e),
+    })
+}
+
+fn error_response(id: Value, (code, message): RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    // Agree to the client's version if we know it, otherwise offer our newest
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "ruskel", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "skeleton",
+            "description": "Render a skeleton of a Rust crate or module: its API as Rust code \
+                with all implementations omitted. The target is a crate name (a dependency of \
+                the current project or a crates.io crate, optionally with @version), a path, or \
+                either followed by ::path::to::module.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "target": { "type": "string", "description": "e.g. serde, serde@1.0.200, serde::de, ./mycrate" },
+                    "filter": { "type": "string", "description": "Only render items matching this path pattern, relative to the path the target names, e.g. de::*Deserialize* for serde" },
+                    "features": { "type": "array", "items": { "type": "string" }, "description": "Features to enable" },
+                    "all_features": { "type": "boolean", "description": "Enable all features" },
+                    "no_default_features": { "type": "boolean", "description": "Disable default features" },
+                    "private": { "type": "boolean", "description": "Include private items" },
+                    "auto_impls": { "type": "boolean", "description": "Include auto-implemented traits" },
+                },
+                "required": ["target"],
+            },
+        },
+        {
+            "name": "search",
+            "description": "Find items in a crate whose names contain the query, ignoring case. \
+                Queries containing :: are matched against full paths. Returns one item per \
+                line, with its kind.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "crate": { "type": "string", "description": "The crate to search, as for skeleton" },
+                    "query": { "type": "string", "description": "e.g. deserialize, de::Error" },
+                    "private": { "type": "boolean", "description": "Include private items" },
+                },
+                "required": ["crate", "query"],
+            },
+        },
+        {
+            "name": "item",
+            "description": "Render a type or trait by its full path, with its impls, the \
+                functions that take or return it, and the traits it appears in the bounds of.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "e.g. serde_json::Value" },
+                    "private": { "type": "boolean", "description": "Include private items" },
+                },
+                "required": ["path"],
+            },
+        },
+    ])
+}
+
+fn call_tool(params: &Value, ruskel: &dyn Fn(&str) -> Ruskel) -> Result<Value, RpcError> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| (INVALID_PARAMS, "missing tool name".to_string()))?;
+    let empty = Map::new();
+    let args = match params.get("arguments") {
+        None | Some(Value::Null) => &empty,
+        Some(Value::Object(args)) => args,
+        Some(_) => return Err((INVALID_PARAMS, "arguments must be an object".to_string())),
+    };
+
+    let output = match name {
+        "skeleton" => {
+            let rs = ruskel(&required_string(args, "target")?)
+                .with_no_default_features(bool_arg(args, "no_default_features")?)
+                .with_all_features(bool_arg(args, "all_features")?)
+                .with_features(strings_arg(args, "features")?);
+            let mut renderer = Renderer::default()
+                .with_private_items(bool_arg(args, "private")?)
+                .with_auto_impls(bool_arg(args, "auto_impls")?);
+            if let Some(filter) = string_arg(args, "filter")? {
+                renderer = renderer.with_filter(&filter);
+            }
+            rs.render_with(renderer)
+        }
+        "search" => {
+            let query = required_string(args, "query")?;
+            ruskel(&required_string(args, "crate")?)
+                .search(&query, bool_arg(args, "private")?)
+                .map(|results| {
+                    if results.is_empty() {
+                        return format!("no items match '{}'", query);
+                    }
+                    results
+                        .iter()
+                        .map(|r| format!("{} {}", r.kind, r.path))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+        }
+        "item" => ruskel(&required_string(args, "path")?)
+            .explain(Renderer::default().with_private_items(bool_arg(args, "private")?)),
+        _ => return Err((INVALID_PARAMS, format!("unknown tool {}", name))),
+    };
+
+    // Failures of the tool itself are results, so the model can see them
+    Ok(match output {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+        Err(e) => {
+            json!({ "content": [{ "type": "text", "text": e.to_string() }], "isError": true })
+        }
+    })
+}
+
+fn string_arg(args: &Map<String, Value>, name: &str) -> Result<Option<String>, RpcError> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err((INVALID_PARAMS, format!("{} must be a string", name))),
+    }
+}
+
+fn required_string(args: &Map<String, Value>, name: &str) -> Result<String, RpcError> {
+    string_arg(args, name)?.ok_or_else(|| (INVALID_PARAMS, format!("missing argument {}", name)))
+}
+
+fn bool_arg(args: &Map<String, Value>, name: &str) -> Result<bool, RpcError> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(_) => Err((INVALID_PARAMS, format!("{} must be a boolean", name))),
+    }
+}
+
+fn strings_arg(args: &Map<String, Value>, name: &str) -> Result<Vec<String>, RpcError> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|v| {
+                v.as_str().map(str::to_string).ok_or_else(|| {
+                    (
+                        INVALID_PARAMS,
+                        format!("{} must be a list of strings", name),
+                    )
+                })
+            })
+            .collect(),
+        Some(_) => Err((
+            INVALID_PARAMS,
+            format!("{} must be a list of strings", name),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Runs a scripted session and returns the responses.
+    fn session(requests: &[Value], ruskel: impl Fn(&str) -> Ruskel) -> Vec<Value> {
+        let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output, ruskel).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn call(id: u64, name: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+    }
+
+    #[test]
+    fn test_protocol() {
+        let responses = session(
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                       "params": {"protocolVersion": "2024-11-05", "capabilities": {}}}),
+                json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "ping"}),
+                json!({"jsonrpc": "2.0", "id": 4, "method": "resources/list"}),
+                call(5, "compile", json!({})),
+                call(6, "skeleton", json!({"target": 1})),
+            ],
+            Ruskel::new,
+        );
+        assert_eq!(responses.len(), 6);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "ruskel");
+
+        let names: Vec<&str> = responses[1]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["skeleton", "search", "item"]);
+
+        assert_eq!(responses[2]["result"], json!({}));
+        assert_eq!(responses[3]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[4]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[5]["error"]["message"], "target must be a string");
+
+        let responses = session(&[], Ruskel::new);
+        assert!(responses.is_empty());
+        let mut output = Vec::new();
+        serve("not json\n".as_bytes(), &mut output, Ruskel::new).unwrap();
+        let response: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn test_tools() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"mcp_tools\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod shapes {\n    pub struct Circle;\n    pub fn circle_area(c: &Circle) -> f64 { 0.0 }\n}\nfn hidden_circle() {}\n",
+        )
+        .unwrap();
+        let target = root.to_str().unwrap();
+
+        let responses = session(
+            &[
+                call(1, "skeleton", json!({ "target": target })),
+                call(2, "search", json!({ "crate": target, "query": "CIRCLE" })),
+                call(
+                    3,
+                    "search",
+                    json!({ "crate": target, "query": "circle", "private": true }),
+                ),
+                call(
+                    4,
+                    "item",
+                    json!({ "path": format!("{}::shapes::Circle", target) }),
+                ),
+                call(
+                    5,
+                    "item",
+                    json!({ "path": format!("{}::shapes::Square", target) }),
+                ),
+                call(
+                    6,
+                    "skeleton",
+                    json!({ "target": format!("{}::shapes", target), "filter": "circle_*" }),
+                ),
+            ],
+            |target| Ruskel::new(target).with_offline(true),
+        );
+        let text = |i: usize| {
+            responses[i]["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+        };
+
+        assert_eq!(responses[0]["result"]["isError"], false);
+        assert!(text(0).contains("pub fn circle_area(c: &Circle) -> f64 {}"));
+        assert_eq!(
+            text(1),
+            "struct mcp_tools::shapes::Circle\nfn mcp_tools::shapes::circle_area"
+        );
+        assert!(text(2).contains("fn mcp_tools::hidden_circle"));
+        assert!(text(3).contains("pub struct Circle;"), "{}", text(3));
+        assert!(text(3).contains("pub fn circle_area(c: &Circle) -> f64 {}"));
+        assert!(!text(3).contains("hidden_circle"));
+        assert_eq!(responses[4]["result"]["isError"], true);
+
+        // The filter is relative to the path the target names
+        assert!(text(5).contains("pub fn circle_area(c: &Circle) -> f64 {}"));
+        assert!(!text(5).contains("pub struct Circle;"), "{}", text(5));
+    }
+}
@@ -0,0 +1,558 @@
+//! A long-running HTTP/JSON server that keeps built crates in memory, and rebuilds local packages
+//! in the background when their sources change.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use libruskel::{LoadedCrate, Renderer, ResolvedPackage, Ruskel};
+use serde_json::{json, Value};
+
+/// How often local packages are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a connection may wait to send its request before it's dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many connections are handled at once. Others wait until a worker is free.
+const WORKERS: usize = 8;
+
+/// How long to wait before accepting again after a failure, such as running out of file
+/// descriptors, so a failure that persists doesn't spin.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Creates a `Ruskel` for a target, with options that apply to every request already set.
+pub type RuskelFactory = dyn Fn(&str) -> Ruskel + Send + Sync;
+
+/// The package a target resolves to and the options that determine how it's built. Requests
+/// that agree on these share a build, whichever way their targets name the package.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    /// The directory of the package's source, which differs between versions and sources.
+    package: PathBuf,
+    features: Vec<String>,
+    all_features: bool,
+    no_default_features: bool,
+}
+
+struct Entry {
+    /// The target the package was first requested by, used to rebuild it.
+    target: String,
+    loaded: Arc<LoadedCrate>,
+    /// The newest modification time among the package's sources when it was built.
+    modified: Option<SystemTime>,
+    builds: usize,
+}
+
+pub struct Server {
+    ruskel: Box<RuskelFactory>,
+    cache: Mutex<HashMap<CacheKey, Entry>>,
+    /// Locks held while a key is built, so concurrent requests for it wait for one build.
+    building: Mutex<HashMap<CacheKey, Arc<Mutex<()>>>>,
+}
+
+/// An HTTP response: status code and JSON body.
+type Response = (u16, Value);
+
+impl Server {
+    pub fn new(ruskel: Box<RuskelFactory>) -> Self {
+        Server {
+            ruskel,
+            cache: Mutex::new(HashMap::new()),
+            building: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Accepts connections and hands them to a fixed pool of worker threads, while a background
+    /// thread watches local packages for changes. Failing to accept a connection is logged, and
+    /// doesn't stop the server.
+    pub fn run(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        let watcher = Arc::clone(&self);
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            watcher.refresh();
+        });
+
+        let (sender, receiver) = mpsc::sync_channel::<TcpStream>(WORKERS);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..WORKERS {
+            let server = Arc::clone(&self);
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || loop {
+                let Ok(stream) = receiver.lock().unwrap().recv() else {
+                    break;
+                };
+                if let Err(e) = server.handle_connection(stream) {
+                    eprintln!("connection error: {}", e);
+                }
+            });
+        }
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if sender.send(stream).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("accept error: {}", e);
+                    thread::sleep(ACCEPT_BACKOFF);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn ruskel(&self, target: &str, key: &CacheKey) -> Ruskel {
+        (self.ruskel)(target)
+            .with_features(key.features.clone())
+            .with_all_features(key.all_features)
+            .with_no_default_features(key.no_default_features)
+    }
+
+    fn cached(&self, key: &CacheKey) -> Option<Arc<LoadedCrate>> {
+        let cache = self.cache.lock().unwrap();
+        cache.get(key).map(|entry| Arc::clone(&entry.loaded))
+    }
+
+    /// Returns the cached crate for the key, building the resolved package first if needed.
+    /// Requests for a key that's being built wait for that build rather than starting another.
+    fn get(
+        &self,
+        key: &CacheKey,
+        target: &str,
+        resolved: &ResolvedPackage,
+    ) -> libruskel::Result<Arc<LoadedCrate>> {
+        if let Some(loaded) = self.cached(key) {
+            return Ok(loaded);
+        }
+        let lock = Arc::clone(
+            self.building
+                .lock()
+                .unwrap()
+                .entry(key.clone())
+                .or_default(),
+        );
+        let _building = lock.lock().unwrap();
+        if let Some(loaded) = self.cached(key) {
+            return Ok(loaded);
+        }
+        let built = self.ruskel(target, key).build(resolved).map(Arc::new);
+        if let Ok(loaded) = &built {
+            let modified = loaded.package_dir().and_then(newest_modification);
+            self.cache.lock().unwrap().insert(
+                key.clone(),
+                Entry {
+                    target: target.to_string(),
+                    loaded: Arc::clone(loaded),
+                    modified,
+                    builds: 1,
+                },
+            );
+        }
+        // Only removed once the crate is cached, so later requests find it there
+        self.building.lock().unwrap().remove(key);
+        built
+    }
+
+    /// Rebuilds cached local packages whose sources have changed since they were built. Requests
+    /// are served from the previous build until the new one is ready.
+    pub fn refresh(&self) {
+        let stale: Vec<(CacheKey, String, Option<SystemTime>)> = self
+            .cache
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(key, entry)| {
+                let modified = newest_modification(entry.loaded.package_dir()?);
+                (modified != entry.modified).then(|| (key.clone(), entry.target.clone(), modified))
+            })
+            .collect();
+
+        for (key, target, modified) in stale {
+            let rebuilt = self.ruskel(&target, &key).load();
+            let mut cache = self.cache.lock().unwrap();
+            let Some(entry) = cache.get_mut(&key) else {
+                continue;
+            };
+            // A failed build, e.g. of code being edited, keeps the last good one
+            match rebuilt {
+                Ok(loaded) => {
+                    entry.loaded = Arc::new(loaded);
+                    entry.builds += 1;
+                }
+                Err(e) => eprintln!("rebuilding {} failed: {}", target, e),
+            }
+            entry.modified = modified;
+        }
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Skip the headers, draining any body so the client sees a clean close
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        io::copy(&mut reader.by_ref().take(content_length), &mut io::sink())?;
+
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => self.handle_request(method, target),
+            _ => error(400, "malformed request"),
+        };
+        let body = body.to_string();
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            status,
+            reason(status),
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    /// Handles a request for a path with a query string, e.g. `/render?target=serde`.
+    pub fn handle_request(&self, method: &str, target: &str) -> Response {
+        if method != "GET" {
+            return error(405, "only GET is supported");
+        }
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let params = parse_query(query);
+        let result = match path {
+            "/render" => self.render(&params),
+            "/search" => self.search(&params),
+            "/raw" => self.raw(&params),
+            "/cache" => Ok(self.cache_status()),
+            _ => return error(404, &format!("no endpoint {}", path)),
+        };
+        result.unwrap_or_else(|e| e)
+    }
+
+    fn render(&self, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let loaded = self.load(params)?;
+        let mut renderer = Renderer::default()
+            .with_private_items(bool_param(params, "private"))
+            .with_auto_impls(bool_param(params, "auto_impls"));
+        if let Some(filter) = params.get("filter") {
+            renderer = renderer.with_filter(filter);
+        }
+        let output = loaded
+            .render(renderer)
+            .map_err(|e| error(500, &e.to_string()))?;
+        Ok((200, json!({ "output": output })))
+    }
+
+    fn search(&self, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let query = required_param(params, "query")?;
+        let loaded = self.load(params)?;
+        let results: Vec<Value> = loaded
+            .search(query, bool_param(params, "private"))
+            .iter()
+            .map(|r| json!({ "path": r.path, "kind": r.kind.to_string() }))
+            .collect();
+        Ok((200, json!({ "results": results })))
+    }
+
+    fn raw(&self, params: &HashMap<String, String>) -> Result<Response, Response> {
+        let loaded = self.load(params)?;
+        let data =
+            serde_json::to_value(loaded.crate_data()).map_err(|e| error(500, &e.to_string()))?;
+        Ok((200, data))
+    }
+
+    fn cache_status(&self) -> Response {
+        let cache = self.cache.lock().unwrap();
+        let mut entries: Vec<Value> = cache
+            .iter()
+            .map(|(key, entry)| {
+                json!({
+                    "target": entry.target,
+                    "package": key.package,
+                    "features": key.features,
+                    "all_features": key.all_features,
+                    "no_default_features": key.no_default_features,
+                    "watched": entry.loaded.package_dir().is_some(),
+                    "builds": entry.builds,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a["target"].as_str().cmp(&b["target"].as_str()));
+        (200, json!({ "entries": entries }))
+    }
+
+    /// Resolves the requested target and returns its crate, from the cache if the package it
+    /// resolves to has been built with the same options.
+    fn load(&self, params: &HashMap<String, String>) -> Result<LoadedCrate, Response> {
+        let target = required_param(params, "target")?;
+        let mut key = CacheKey {
+            package: PathBuf::new(),
+            features: params
+                .get("features")
+                .map(|f| {
+                    f.split(',')
+                        .filter(|f| !f.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            all_features: bool_param(params, "all_features"),
+            no_default_features: bool_param(params, "no_default_features"),
+        };
+        let failed = |e: libruskel::RuskelError| error(500, &e.to_string());
+        let resolved = self.ruskel(target, &key).resolve().map_err(failed)?;
+        key.package = fs::canonicalize(resolved.package_root())
+            .unwrap_or_else(|_| resolved.package_root().to_path_buf());
+        let loaded = self.get(&key, target, &resolved).map_err(failed)?;
+        Ok(loaded.retarget(&resolved))
+    }
+}
+
+fn error(status: u16, message: &str) -> Response {
+    (status, json!({ "error": message }))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+fn required_param<'a>(
+    params: &'a HashMap<String, String>,
+    name: &str,
+) -> Result<&'a str, Response> {
+    params
+        .get(name)
+        .map(String::as_str)
+        .ok_or_else(|| error(400, &format!("missing parameter {}", name)))
+}
+
+fn bool_param(params: &HashMap<String, String>, name: &str) -> bool {
+    params
+        .get(name)
+        .is_some_and(|v| matches!(v.as_str(), "" | "1" | "true" | "yes"))
+}
+
+/// Parses a URL query string into decoded name/value pairs.
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (None, b'+') => decoded.push(b' '),
+            (None, b) => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Returns the newest modification time of the package's manifest and sources, skipping the
+/// target directory and hidden directories.
+fn newest_modification(dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    let Ok(entries) = fs::read_dir(dir) else {
+        return newest;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let modified = if file_type.is_dir() {
+            newest_modification(&entry.path())
+        } else {
+            entry.metadata().and_then(|m| m.modified()).ok()
+        };
+        newest = newest.max(modified);
+    }
+    newest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::net::Shutdown;
+
+    #[test]
+    fn test_parse_query() {
+        let params = parse_query("target=serde%3A%3Ade&filter=a+b&private&bad=%zz%4");
+        assert_eq!(params["target"], "serde::de");
+        assert_eq!(params["filter"], "a b");
+        assert_eq!(params["private"], "");
+        assert_eq!(params["bad"], "%zz%4");
+        assert!(bool_param(&params, "private"));
+        assert!(!bool_param(&params, "auto_impls"));
+    }
+
+    #[test]
+    fn test_render_filter() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"served\"]\nresolver = \"2\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("served/src")).unwrap();
+        fs::write(
+            root.join("served/Cargo.toml"),
+            "[package]\nname = \"served\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("served/src/lib.rs"),
+            "pub fn first() {}\npub fn second() {}\n",
+        )
+        .unwrap();
+
+        let server = Server::new(Box::new(|target| Ruskel::new(target).with_offline(true)));
+        // The target names the crate, and the filter is relative to it
+        let (status, body) = server.handle_request(
+            "GET",
+            &format!("/render?target={}::served&filter=first", root.display()),
+        );
+        assert_eq!(status, 200, "{}", body);
+        let output = body["output"].as_str().unwrap();
+        assert!(output.contains("pub fn first() {}"), "{}", output);
+        assert!(!output.contains("second"), "{}", output);
+    }
+
+    #[test]
+    fn test_server() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"served\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/lib.rs"), "pub fn first() {}\n").unwrap();
+        let target = root.to_str().unwrap();
+
+        let server = Arc::new(Server::new(Box::new(|target| {
+            Ruskel::new(target).with_offline(true)
+        })));
+        let get = |path: &str| server.handle_request("GET", path);
+
+        // Concurrent requests for a crate that isn't cached share one build
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(get(&format!("/raw?target={}", target)).0, 200));
+            }
+        });
+
+        let (status, body) = get(&format!("/render?target={}", target));
+        assert_eq!(status, 200);
+        assert!(body["output"]
+            .as_str()
+            .unwrap()
+            .contains("pub fn first() {}"));
+
+        let (status, body) = get(&format!("/search?target={}&query=FIR", target));
+        assert_eq!(status, 200);
+        assert_eq!(
+            body["results"],
+            json!([{ "path": "served::first", "kind": "fn" }])
+        );
+
+        let (status, body) = get(&format!("/raw?target={}", target));
+        assert_eq!(status, 200);
+        assert!(body["index"].is_object());
+
+        // Another target naming the same package shares its entry
+        assert_eq!(get(&format!("/raw?target={}/src/..", target)).0, 200);
+
+        // All the requests were served from one build
+        let (_, body) = get("/cache");
+        assert_eq!(body["entries"].as_array().unwrap().len(), 1);
+        assert_eq!(body["entries"][0]["builds"], 1);
+        assert_eq!(body["entries"][0]["watched"], true);
+
+        assert_eq!(get("/render").0, 400);
+        assert_eq!(get("/unknown").0, 404);
+        assert_eq!(server.handle_request("POST", "/render").0, 405);
+
+        // Changing the source rebuilds the crate
+        fs::write(root.join("src/lib.rs"), "pub fn second() {}\n").unwrap();
+        File::options()
+            .write(true)
+            .open(root.join("src/lib.rs"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        server.refresh();
+        let (_, body) = get(&format!("/render?target={}", target));
+        assert!(body["output"]
+            .as_str()
+            .unwrap()
+            .contains("pub fn second() {}"));
+        let (_, body) = get("/cache");
+        assert_eq!(body["entries"][0]["builds"], 2);
+
+        // Requests over a socket get the same answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || server.run(listener));
+        // A client that never sends its request only holds up one worker
+        let _idle = TcpStream::connect(addr).unwrap();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET /cache HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("}"), "{}", response);
+    }
+}